@@ -1,11 +1,8 @@
 use ad983x::{Ad983x, FrequencyRegister};
-use embedded_hal::spi::blocking::ExclusiveDevice;
-use linux_embedded_hal::{Spidev, SysfsPin};
+use linux_embedded_hal::SpidevDevice;
 
 fn main() {
-    let spi = Spidev::open("/dev/spidev0.0").unwrap();
-    let chip_select = SysfsPin::new(25);
-    let dev = ExclusiveDevice::new(spi, chip_select);
+    let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
     let mut dds = Ad983x::new_ad9833(dev);
     dds.reset().unwrap(); // reset is necessary before operation
     dds.set_frequency(FrequencyRegister::F0, 4724).unwrap();
@@ -0,0 +1,370 @@
+use crate::{
+    marker, Ad983x, Config, Error, IncrementInterval, IncrementIntervalBase,
+    IncrementIntervalMultiplier, IncrementTrigger, OutputWaveform, PoweredDown, SquareWave,
+    SquareWavePin, SweepConfig, SyncOutput, Variant, WriteWord,
+};
+
+/// Register addresses and control register bits of AD5930/AD5932 devices.
+///
+/// The register address uses the 4 MSBs (D15-D12) of each word.
+pub(crate) struct BitFlags;
+
+impl BitFlags {
+    pub(crate) const ADDR_CONTROL: u16 = 0b0000 << 12;
+    pub(crate) const ADDR_NINCR: u16 = 0b0001 << 12;
+    pub(crate) const ADDR_DELTA_F_LSB: u16 = 0b0010 << 12;
+    pub(crate) const ADDR_DELTA_F_MSB: u16 = 0b0011 << 12;
+    pub(crate) const ADDR_TINT_OUTPUT_CYCLES: u16 = 0b0100 << 12;
+    pub(crate) const ADDR_TINT_MCLK_PERIODS: u16 = 0b0110 << 12;
+    pub(crate) const ADDR_FSTART_LSB: u16 = 0b1100 << 12;
+    pub(crate) const ADDR_FSTART_MSB: u16 = 0b1101 << 12;
+    pub(crate) const B24: u16 = 1 << 11;
+    pub(crate) const DAC_ENABLE: u16 = 1 << 10;
+    pub(crate) const SINE_TRI: u16 = 1 << 9;
+    pub(crate) const MSBOUTEN: u16 = 1 << 8;
+    pub(crate) const CW_BURST: u16 = 1 << 7; // AD5930 only. Reserved on AD5932.
+    pub(crate) const D6: u16 = 1 << 6;
+    pub(crate) const INT_EXT_INCR: u16 = 1 << 5;
+    pub(crate) const D4: u16 = 1 << 4;
+    pub(crate) const SYNCSEL: u16 = 1 << 3;
+    pub(crate) const SYNCOUTEN: u16 = 1 << 2;
+    pub(crate) const D1: u16 = 1 << 1;
+    pub(crate) const D0: u16 = 1;
+    pub(crate) const DELTA_F_NEGATIVE: u16 = 1 << 11;
+}
+
+const MAX_FREQUENCY: u32 = (1 << 24) - 1;
+const MAX_FREQUENCY_INCREMENT: i32 = (1 << 23) - 1;
+const MIN_INCREMENTS: u16 = 2;
+const MAX_INCREMENTS: u16 = (1 << 12) - 1;
+const MIN_INTERVAL_COUNT: u16 = 2;
+const MAX_INTERVAL_COUNT: u16 = (1 << 11) - 1;
+
+impl<DEV> Ad983x<DEV, marker::Ad5930Ad5932> {
+    pub(crate) fn create_ad5930_ad5932(spi: DEV, variant: Variant) -> Self {
+        let mut dev = Self::create(spi, variant);
+        // Reserved bits must be set. On the AD5930 the output is continuous
+        // and the sweep goes from the start frequency upwards (saw sweep).
+        let reserved = match variant {
+            Variant::Ad5930 => BitFlags::CW_BURST | BitFlags::D1 | BitFlags::D0,
+            _ => BitFlags::CW_BURST | BitFlags::D6 | BitFlags::D4 | BitFlags::D1 | BitFlags::D0,
+        };
+        dev.control = Config {
+            bits: reserved | BitFlags::B24 | BitFlags::DAC_ENABLE | BitFlags::SINE_TRI,
+        };
+        dev
+    }
+}
+
+impl<DEV, E> Ad983x<DEV, marker::Ad5930Ad5932>
+where
+    DEV: WriteWord<Error = E>,
+{
+    /// Create a new instance of an AD5930 device.
+    ///
+    /// Remember to call `set_sweep()` before starting a sweep after power up.
+    pub fn new_ad5930(spi: DEV) -> Self {
+        Self::create_ad5930_ad5932(spi, Variant::Ad5930)
+    }
+
+    /// Create a new instance of an AD5932 device.
+    ///
+    /// Remember to call `set_sweep()` before starting a sweep after power up.
+    pub fn new_ad5932(spi: DEV) -> Self {
+        Self::create_ad5930_ad5932(spi, Variant::Ad5932)
+    }
+
+    /// Configure a frequency sweep
+    ///
+    /// This writes the control register followed by all the sweep registers.
+    /// The sweep is started with a rising edge on the CTRL pin.
+    ///
+    /// Returns `Error::ValueTooWide` or `Error::OutOfRange` if any of the values is out of range.
+    /// Nothing is written to the device in that case.
+    pub fn set_sweep(&mut self, config: &SweepConfig) -> Result<(), Error<E>> {
+        check_frequency(config.start_frequency)?;
+        check_frequency_increment(config.frequency_increment)?;
+        check_increments(config.increments)?;
+        check_increment_interval(&config.increment_interval)?;
+        trace_op!("set_sweep {:?}", config);
+        let control = self.control;
+        self.write_control(control)?;
+        self.set_start_frequency(config.start_frequency)?;
+        self.set_frequency_increment(config.frequency_increment)?;
+        self.set_increments(config.increments)?;
+        self.set_increment_interval(config.increment_interval)
+    }
+
+    /// Set the sweep start frequency as a 24-bit word
+    ///
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 24 bits.
+    pub fn set_start_frequency(&mut self, value: u32) -> Result<(), Error<E>> {
+        check_frequency(value)?;
+        trace_op!("set_start_frequency {:#x}", value);
+        self.write_sequence(&[
+            BitFlags::ADDR_FSTART_LSB | (value & 0xFFF) as u16,
+            BitFlags::ADDR_FSTART_MSB | (value >> 12) as u16,
+        ])
+    }
+
+    /// Set the sweep start frequency in Hz
+    ///
+    /// The value written into the register is calculated from the
+    /// configured MCLK frequency. See [`set_mclk_frequency()`](#method.set_mclk_frequency).
+    /// Returns `Error::MclkFrequencyNotSet` if no MCLK frequency has been configured or
+    /// `Error::FrequencyOutOfRange` if the frequency is negative or exceeds half
+    /// the MCLK frequency.
+    pub fn set_start_frequency_hz(&mut self, frequency_hz: f64) -> Result<(), Error<E>> {
+        let value = self.frequency_register_value(frequency_hz)?;
+        trace_op!("set_start_frequency_hz {} Hz", frequency_hz);
+        self.set_start_frequency(value)
+    }
+
+    /// Set the frequency increment as a signed 24-bit word
+    ///
+    /// A negative value sweeps down from the start frequency.
+    /// Returns `Error::OutOfRange` if the value is outside the range
+    /// -(2^23 - 1) to 2^23 - 1.
+    pub fn set_frequency_increment(&mut self, value: i32) -> Result<(), Error<E>> {
+        check_frequency_increment(value)?;
+        trace_op!("set_frequency_increment {}", value);
+        let magnitude = value.unsigned_abs();
+        let sign = if value < 0 {
+            BitFlags::DELTA_F_NEGATIVE
+        } else {
+            0
+        };
+        self.write_sequence(&[
+            BitFlags::ADDR_DELTA_F_LSB | (magnitude & 0xFFF) as u16,
+            BitFlags::ADDR_DELTA_F_MSB | sign | (magnitude >> 12) as u16,
+        ])
+    }
+
+    /// Set the number of frequency increments in the sweep
+    ///
+    /// Returns `Error::OutOfRange` if the value is not between 2 and 4095.
+    pub fn set_increments(&mut self, value: u16) -> Result<(), Error<E>> {
+        check_increments(value)?;
+        trace_op!("set_increments {}", value);
+        self.write(BitFlags::ADDR_NINCR | value)
+    }
+
+    /// Set the time interval between the frequency increments
+    ///
+    /// Returns `Error::OutOfRange` if the count is not between 2 and 2047.
+    pub fn set_increment_interval(&mut self, interval: IncrementInterval) -> Result<(), Error<E>> {
+        check_increment_interval(&interval)?;
+        trace_op!("set_increment_interval {:?}", interval);
+        let address = match interval.base {
+            IncrementIntervalBase::OutputCycles => BitFlags::ADDR_TINT_OUTPUT_CYCLES,
+            IncrementIntervalBase::MclkPeriods => BitFlags::ADDR_TINT_MCLK_PERIODS,
+        };
+        let multiplier = match interval.multiplier {
+            IncrementIntervalMultiplier::X1 => 0b00,
+            IncrementIntervalMultiplier::X5 => 0b01,
+            IncrementIntervalMultiplier::X100 => 0b10,
+            IncrementIntervalMultiplier::X500 => 0b11,
+        };
+        self.write(address | (multiplier << 11) | interval.count)
+    }
+
+    /// Set the trigger of the frequency increments
+    pub fn set_increment_trigger(&mut self, trigger: IncrementTrigger) -> Result<(), Error<E>> {
+        let control = match trigger {
+            IncrementTrigger::Internal => self.control.with_low(BitFlags::INT_EXT_INCR),
+            IncrementTrigger::External => self.control.with_high(BitFlags::INT_EXT_INCR),
+        };
+        trace_op!("set_increment_trigger {:?}", trigger);
+        self.write_control(control)
+    }
+
+    /// Set the SYNCOUT pin configuration
+    pub fn set_sync_output(&mut self, config: SyncOutput) -> Result<(), Error<E>> {
+        let control = match config {
+            SyncOutput::Disabled => self.control.with_low(BitFlags::SYNCOUTEN),
+            SyncOutput::EachIncrement => self
+                .control
+                .with_high(BitFlags::SYNCOUTEN)
+                .with_low(BitFlags::SYNCSEL),
+            SyncOutput::EndOfSweep => self
+                .control
+                .with_high(BitFlags::SYNCOUTEN)
+                .with_high(BitFlags::SYNCSEL),
+        };
+        trace_op!("set_sync_output {:?}", config);
+        self.write_control(control)
+    }
+
+    /// Set the output waveform
+    ///
+    /// Will return `Error::UnsupportedWaveform` for `SquareMsbOfDac` and `SquareMsbOfDacDiv2`
+    /// as this is not available on AD5930/AD5932 devices. To output a square wave
+    /// on the MSBOUT pin, please use `set_square_wave_output()`.
+    pub fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Error<E>> {
+        let control = match waveform {
+            OutputWaveform::Sinusoidal => self.control.with_high(BitFlags::SINE_TRI),
+            OutputWaveform::Triangle => self.control.with_low(BitFlags::SINE_TRI),
+            OutputWaveform::SquareMsbOfDac | OutputWaveform::SquareMsbOfDacDiv2 => {
+                return Err(Error::UnsupportedWaveform(waveform))
+            }
+        };
+        trace_op!("set_output_waveform {:?}", waveform);
+        self.write_control(control)
+    }
+
+    /// Output a square wave on the MSBOUT pin
+    ///
+    /// The VOUT pin keeps the configured output waveform.
+    /// Returns `Error::Unsupported` for `SquareWave::MsbOfDacDiv2`.
+    pub fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Error<E>> {
+        match square_wave {
+            SquareWave::MsbOfDac => {
+                trace_op!("set_square_wave_output {:?}", square_wave);
+                let control = self.control.with_high(BitFlags::MSBOUTEN);
+                self.write_control(control)?;
+                Ok(SquareWavePin::MsbOut)
+            }
+            SquareWave::MsbOfDacDiv2 => Err(Error::Unsupported),
+        }
+    }
+
+    /// Disable the square wave output on the MSBOUT pin (default)
+    pub fn disable_square_wave_output(&mut self) -> Result<(), Error<E>> {
+        let control = self.control.with_low(BitFlags::MSBOUTEN);
+        trace_op!("disable_square_wave_output");
+        self.write_control(control)
+    }
+
+    /// Set device parts powered-down state.
+    ///
+    /// Only the DAC can be powered down through software. This is useful
+    /// when only the MSBOUT pin is used.
+    /// Returns `Error::Unsupported` for `PoweredDown::InternalClock` and
+    /// `PoweredDown::DacAndInternalClock`. Use the STANDBY pin for that.
+    pub fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Error<E>> {
+        let control = match config {
+            PoweredDown::Nothing => self.control.with_high(BitFlags::DAC_ENABLE),
+            PoweredDown::Dac => self.control.with_low(BitFlags::DAC_ENABLE),
+            PoweredDown::InternalClock | PoweredDown::DacAndInternalClock => {
+                return Err(Error::Unsupported)
+            }
+        };
+        trace_op!("set_powered_down {:?}", config);
+        self.write_control(control)
+    }
+
+    /// Write the control register with the current driver state.
+    ///
+    /// This brings the device back in sync after an SPI communication error.
+    pub fn resync(&mut self) -> Result<(), Error<E>> {
+        trace_op!("resync");
+        let control = self.control;
+        self.write_control(control)
+    }
+
+    fn write_control(&mut self, control: Config) -> Result<(), Error<E>> {
+        trace_op!(
+            "control {:#06x} -> {:#06x}",
+            self.control.bits,
+            control.bits
+        );
+        let payload = BitFlags::ADDR_CONTROL | (control.bits & 0x0FFF);
+        self.with_retries(|dev| dev.write_word(payload))?;
+        self.control = control;
+        self.desynchronized = false;
+        Ok(())
+    }
+
+    /// Write a word to a register other than the control register.
+    ///
+    /// The control register is written first if its state is unknown.
+    fn write(&mut self, payload: u16) -> Result<(), Error<E>> {
+        self.write_sequence(&[payload])
+    }
+
+    /// Write words to registers other than the control register.
+    ///
+    /// The control register is written first if its state is unknown, also
+    /// before sending the whole sequence again on retries.
+    fn write_sequence(&mut self, words: &[u16]) -> Result<(), Error<E>> {
+        self.with_retries(|dev| {
+            if dev.desynchronized {
+                // Written without retries of its own, the whole sequence
+                // is already retried.
+                trace_op!("resync");
+                dev.write_word(BitFlags::ADDR_CONTROL | (dev.control.bits & 0x0FFF))?;
+                dev.desynchronized = false;
+            }
+            words.iter().try_for_each(|word| dev.write_word(*word))
+        })
+    }
+
+    fn write_word(&mut self, payload: u16) -> Result<(), Error<E>> {
+        trace_word!(
+            "word {:#06x}: {} = {:#x}",
+            payload,
+            register_name(payload),
+            payload & 0x0FFF
+        );
+        self.spi.write_word(payload).map_err(|e| {
+            self.desynchronized = true;
+            Error::Spi(e)
+        })
+    }
+}
+
+/// Name of the register written to by a word
+#[cfg(any(feature = "log", feature = "defmt"))]
+fn register_name(payload: u16) -> &'static str {
+    match payload & (0b1111 << 12) {
+        BitFlags::ADDR_CONTROL => "CONTROL",
+        BitFlags::ADDR_NINCR => "NINCR",
+        BitFlags::ADDR_DELTA_F_LSB => "DELTA_F LSB",
+        BitFlags::ADDR_DELTA_F_MSB => "DELTA_F MSB",
+        BitFlags::ADDR_TINT_OUTPUT_CYCLES => "TINT output cycles",
+        BitFlags::ADDR_TINT_MCLK_PERIODS => "TINT MCLK periods",
+        BitFlags::ADDR_FSTART_LSB => "FSTART LSB",
+        BitFlags::ADDR_FSTART_MSB => "FSTART MSB",
+        _ => "unknown",
+    }
+}
+
+fn check_frequency<E>(value: u32) -> Result<(), Error<E>> {
+    if value > MAX_FREQUENCY {
+        return Err(Error::ValueTooWide { bits: 24 });
+    }
+    Ok(())
+}
+
+fn check_frequency_increment<E>(value: i32) -> Result<(), Error<E>> {
+    if !(-MAX_FREQUENCY_INCREMENT..=MAX_FREQUENCY_INCREMENT).contains(&value) {
+        return Err(Error::OutOfRange {
+            min: -MAX_FREQUENCY_INCREMENT,
+            max: MAX_FREQUENCY_INCREMENT,
+        });
+    }
+    Ok(())
+}
+
+fn check_increments<E>(value: u16) -> Result<(), Error<E>> {
+    if !(MIN_INCREMENTS..=MAX_INCREMENTS).contains(&value) {
+        return Err(Error::OutOfRange {
+            min: i32::from(MIN_INCREMENTS),
+            max: i32::from(MAX_INCREMENTS),
+        });
+    }
+    Ok(())
+}
+
+fn check_increment_interval<E>(interval: &IncrementInterval) -> Result<(), Error<E>> {
+    if !(MIN_INTERVAL_COUNT..=MAX_INTERVAL_COUNT).contains(&interval.count) {
+        return Err(Error::OutOfRange {
+            min: i32::from(MIN_INTERVAL_COUNT),
+            max: i32::from(MAX_INTERVAL_COUNT),
+        });
+    }
+    Ok(())
+}
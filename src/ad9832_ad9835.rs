@@ -0,0 +1,370 @@
+use crate::{
+    common::check_value_fits, marker, Ad983x, Config, ControlSource, Error, ExtendedPhaseRegister,
+    FrequencyRegister, PhaseRegister, PoweredDown, Variant, WriteWord,
+};
+
+/// Command and shadow register bits of AD9832/AD9835 devices.
+///
+/// The commands use the 4 MSBs (C3-C0). The shadow register uses the bit
+/// positions that FSELECT, PSEL1, PSEL0, SLEEP and RESET have in their
+/// commands. SYNC and SELSRC are kept in the otherwise unused 2 LSBs.
+pub(crate) struct BitFlags;
+
+impl BitFlags {
+    pub(crate) const CMD_PHASE_16: u16 = 0b0000 << 12;
+    pub(crate) const CMD_PHASE_DEFER: u16 = 0b0001 << 12;
+    pub(crate) const CMD_FREQ_16: u16 = 0b0010 << 12;
+    pub(crate) const CMD_FREQ_DEFER: u16 = 0b0011 << 12;
+    pub(crate) const CMD_SELECT: u16 = 0b0110 << 12;
+    pub(crate) const CMD_SYNC_SELSRC: u16 = 0b1000 << 12;
+    pub(crate) const CMD_SLEEP_RESET_CLR: u16 = 0b1100 << 12;
+    pub(crate) const SLEEP: u16 = 1 << 13;
+    pub(crate) const RESET: u16 = 1 << 12;
+    pub(crate) const FSELECT: u16 = 1 << 11;
+    pub(crate) const PSEL1: u16 = 1 << 10;
+    pub(crate) const PSEL0: u16 = 1 << 9;
+    pub(crate) const SYNC: u16 = 1 << 1;
+    pub(crate) const SELSRC: u16 = 1;
+    // Position of SYNC and SELSRC in their command
+    pub(crate) const CMD_SYNC: u16 = 1 << 13;
+    pub(crate) const CMD_SELSRC: u16 = 1 << 12;
+}
+
+impl From<PhaseRegister> for ExtendedPhaseRegister {
+    fn from(register: PhaseRegister) -> Self {
+        match register {
+            PhaseRegister::P0 => ExtendedPhaseRegister::P0,
+            PhaseRegister::P1 => ExtendedPhaseRegister::P1,
+        }
+    }
+}
+
+impl<DEV> Ad983x<DEV, marker::Ad9832Ad9835> {
+    pub(crate) fn create_ad9832_ad9835(spi: DEV, variant: Variant) -> Self {
+        let mut dev = Self::create(spi, variant);
+        // After power up the registers are selected through the hardware pins
+        dev.control = Config {
+            bits: BitFlags::RESET,
+        };
+        dev
+    }
+}
+
+impl<DEV, E> Ad983x<DEV, marker::Ad9832Ad9835>
+where
+    DEV: WriteWord<Error = E>,
+{
+    /// Create a new instance of an AD9832 device.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new_ad9832(spi: DEV) -> Self {
+        Self::create_ad9832_ad9835(spi, Variant::Ad9832)
+    }
+
+    /// Create a new instance of an AD9835 device.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new_ad9835(spi: DEV) -> Self {
+        Self::create_ad9832_ad9835(spi, Variant::Ad9835)
+    }
+
+    /// Resets the phase accumulator and leaves the device disabled.
+    pub fn reset(&mut self) -> Result<(), Error<E>> {
+        self.disable()
+    }
+
+    /// Disable the device (enable reset)
+    pub fn disable(&mut self) -> Result<(), Error<E>> {
+        trace_op!("disable");
+        let control = self.control.with_high(BitFlags::RESET);
+        self.write_sleep_reset(control)
+    }
+
+    /// Enable the device (disable reset)
+    pub fn enable(&mut self) -> Result<(), Error<E>> {
+        trace_op!("enable");
+        let control = self.control.with_low(BitFlags::RESET);
+        self.write_sleep_reset(control)
+    }
+
+    /// Set the frequency as a 32-bit word
+    pub fn set_frequency(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<(), Error<E>> {
+        trace_op!("set_frequency {:?} = {:#x}", register, value);
+        let address = match register {
+            FrequencyRegister::F0 => 0b0000,
+            FrequencyRegister::F1 => 0b0100,
+        };
+        let [lsb_defer, lsb] = words_16(
+            BitFlags::CMD_FREQ_DEFER,
+            BitFlags::CMD_FREQ_16,
+            address,
+            value as u16,
+        );
+        let [msb_defer, msb] = words_16(
+            BitFlags::CMD_FREQ_DEFER,
+            BitFlags::CMD_FREQ_16,
+            address | 0b0010,
+            (value >> 16) as u16,
+        );
+        self.write_sequence(&[lsb_defer, lsb, msb_defer, msb])
+    }
+
+    /// Set the frequency in Hz
+    ///
+    /// The value written into the frequency register is calculated from the
+    /// configured MCLK frequency. See [`set_mclk_frequency()`](#method.set_mclk_frequency).
+    /// Returns `Error::MclkFrequencyNotSet` if no MCLK frequency has been configured or
+    /// `Error::FrequencyOutOfRange` if the frequency is negative or exceeds half
+    /// the MCLK frequency.
+    pub fn set_frequency_hz(
+        &mut self,
+        register: FrequencyRegister,
+        frequency_hz: f64,
+    ) -> Result<(), Error<E>> {
+        let value = self.frequency_register_value(frequency_hz)?;
+        self.set_frequency(register, value)
+    }
+
+    /// Select the frequency register that is used
+    ///
+    /// Note: this is ignored if hardware pin control source is selected.
+    pub fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Error<E>> {
+        trace_op!("select_frequency {:?}", register);
+        let control = match register {
+            FrequencyRegister::F0 => self.control.with_low(BitFlags::FSELECT),
+            FrequencyRegister::F1 => self.control.with_high(BitFlags::FSELECT),
+        };
+        self.write_selection(control)
+    }
+
+    /// Set a phase register (12-bit value)
+    ///
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 12 bits.
+    pub fn set_phase<R>(&mut self, register: R, value: u16) -> Result<(), Error<E>>
+    where
+        R: Into<ExtendedPhaseRegister>,
+    {
+        check_value_fits(value, 12)?;
+        let register = register.into();
+        trace_op!("set_phase {:?} = {}", register, value);
+        let address = match register {
+            ExtendedPhaseRegister::P0 => 0b1000,
+            ExtendedPhaseRegister::P1 => 0b1010,
+            ExtendedPhaseRegister::P2 => 0b1100,
+            ExtendedPhaseRegister::P3 => 0b1110,
+        };
+        self.write_sequence(&words_16(
+            BitFlags::CMD_PHASE_DEFER,
+            BitFlags::CMD_PHASE_16,
+            address,
+            value,
+        ))
+    }
+
+    /// Select the phase register that is used
+    ///
+    /// Note: this is ignored if hardware pin control source is selected.
+    pub fn select_phase<R>(&mut self, register: R) -> Result<(), Error<E>>
+    where
+        R: Into<ExtendedPhaseRegister>,
+    {
+        let register = register.into();
+        trace_op!("select_phase {:?}", register);
+        let control = match register {
+            ExtendedPhaseRegister::P0 => self
+                .control
+                .with_low(BitFlags::PSEL1)
+                .with_low(BitFlags::PSEL0),
+            ExtendedPhaseRegister::P1 => self
+                .control
+                .with_low(BitFlags::PSEL1)
+                .with_high(BitFlags::PSEL0),
+            ExtendedPhaseRegister::P2 => self
+                .control
+                .with_high(BitFlags::PSEL1)
+                .with_low(BitFlags::PSEL0),
+            ExtendedPhaseRegister::P3 => self
+                .control
+                .with_high(BitFlags::PSEL1)
+                .with_high(BitFlags::PSEL0),
+        };
+        self.write_selection(control)
+    }
+
+    /// Set device parts powered-down state.
+    ///
+    /// These devices can only power down the DAC and the internal clock together.
+    /// Returns `Error::Unsupported` for `PoweredDown::Dac` and `PoweredDown::InternalClock`.
+    pub fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Error<E>> {
+        let control = match config {
+            PoweredDown::Nothing => self.control.with_low(BitFlags::SLEEP),
+            PoweredDown::DacAndInternalClock => self.control.with_high(BitFlags::SLEEP),
+            PoweredDown::Dac | PoweredDown::InternalClock => return Err(Error::Unsupported),
+        };
+        trace_op!("set_powered_down {:?}", config);
+        self.write_sleep_reset(control)
+    }
+
+    /// Set the control source used for the frequency and phase register selection.
+    ///
+    /// After power up the hardware pins are used.
+    pub fn set_control_source(&mut self, source: ControlSource) -> Result<(), Error<E>> {
+        trace_op!("set_control_source {:?}", source);
+        let control = match source {
+            ControlSource::Software => self.control.with_high(BitFlags::SELSRC),
+            ControlSource::HardwarePins => self.control.with_low(BitFlags::SELSRC),
+        };
+        self.write_sync_selsrc(control)
+    }
+
+    /// Synchronize the reading of the FSELECT, PSEL0 and PSEL1 pins
+    /// with the rising edge of MCLK.
+    pub fn enable_selection_sync(&mut self) -> Result<(), Error<E>> {
+        trace_op!("enable_selection_sync");
+        let control = self.control.with_high(BitFlags::SYNC);
+        self.write_sync_selsrc(control)
+    }
+
+    /// Read the FSELECT, PSEL0 and PSEL1 pins asynchronously (default).
+    pub fn disable_selection_sync(&mut self) -> Result<(), Error<E>> {
+        trace_op!("disable_selection_sync");
+        let control = self.control.with_low(BitFlags::SYNC);
+        self.write_sync_selsrc(control)
+    }
+
+    /// Write all the control commands with the current driver state.
+    ///
+    /// This brings the device back in sync after an SPI communication error.
+    pub fn resync(&mut self) -> Result<(), Error<E>> {
+        trace_op!("resync");
+        self.with_retries(|dev| dev.write_control_words())
+    }
+
+    /// Write all the control words once, without retries.
+    fn write_control_words(&mut self) -> Result<(), Error<E>> {
+        let words = [
+            sleep_reset_word(self.control),
+            sync_selsrc_word(self.control),
+            selection_word(self.control),
+        ];
+        words.iter().try_for_each(|word| self.write_word(*word))?;
+        self.desynchronized = false;
+        Ok(())
+    }
+
+    fn write_sleep_reset(&mut self, control: Config) -> Result<(), Error<E>> {
+        trace_op!(
+            "control {:#06x} -> {:#06x}",
+            self.control.bits,
+            control.bits
+        );
+        self.write(sleep_reset_word(control))?;
+        self.control = control;
+        Ok(())
+    }
+
+    fn write_sync_selsrc(&mut self, control: Config) -> Result<(), Error<E>> {
+        trace_op!(
+            "control {:#06x} -> {:#06x}",
+            self.control.bits,
+            control.bits
+        );
+        self.write(sync_selsrc_word(control))?;
+        self.control = control;
+        Ok(())
+    }
+
+    fn write_selection(&mut self, control: Config) -> Result<(), Error<E>> {
+        trace_op!(
+            "control {:#06x} -> {:#06x}",
+            self.control.bits,
+            control.bits
+        );
+        self.write(selection_word(control))?;
+        self.control = control;
+        Ok(())
+    }
+
+    /// Write a command word.
+    ///
+    /// All the control commands are written first if their state is unknown.
+    fn write(&mut self, payload: u16) -> Result<(), Error<E>> {
+        self.write_sequence(&[payload])
+    }
+
+    /// Write a sequence of command words.
+    ///
+    /// All the control commands are written first if their state is unknown,
+    /// also before sending the whole sequence again on retries.
+    fn write_sequence(&mut self, words: &[u16]) -> Result<(), Error<E>> {
+        self.with_retries(|dev| {
+            if dev.desynchronized {
+                // Written without retries of their own, the whole sequence
+                // is already retried.
+                trace_op!("resync");
+                dev.write_control_words()?;
+            }
+            words.iter().try_for_each(|word| dev.write_word(*word))
+        })
+    }
+
+    fn write_word(&mut self, payload: u16) -> Result<(), Error<E>> {
+        trace_word!(
+            "word {:#06x}: {} = {:#x}",
+            payload,
+            command_name(payload),
+            payload & 0x0FFF
+        );
+        self.spi.write_word(payload).map_err(|e| {
+            self.desynchronized = true;
+            Error::Spi(e)
+        })
+    }
+}
+
+/// Words to write 16 bits through the defer register.
+/// `address` selects the register holding the low byte.
+fn words_16(defer_command: u16, command: u16, address: u16, value: u16) -> [u16; 2] {
+    [
+        defer_command | (address << 8) | (value & 0xFF),
+        command | ((address + 1) << 8) | (value >> 8),
+    ]
+}
+
+fn sleep_reset_word(control: Config) -> u16 {
+    BitFlags::CMD_SLEEP_RESET_CLR | (control.bits & (BitFlags::SLEEP | BitFlags::RESET))
+}
+
+fn sync_selsrc_word(control: Config) -> u16 {
+    let mut payload = BitFlags::CMD_SYNC_SELSRC;
+    if control.bits & BitFlags::SYNC != 0 {
+        payload |= BitFlags::CMD_SYNC;
+    }
+    if control.bits & BitFlags::SELSRC != 0 {
+        payload |= BitFlags::CMD_SELSRC;
+    }
+    payload
+}
+
+fn selection_word(control: Config) -> u16 {
+    BitFlags::CMD_SELECT | (control.bits & (BitFlags::FSELECT | BitFlags::PSEL1 | BitFlags::PSEL0))
+}
+
+/// Name of the command contained in a word
+#[cfg(any(feature = "log", feature = "defmt"))]
+fn command_name(payload: u16) -> &'static str {
+    match payload & (0b1111 << 12) {
+        BitFlags::CMD_PHASE_16 => "PHASE 16-bit",
+        BitFlags::CMD_PHASE_DEFER => "PHASE defer",
+        BitFlags::CMD_FREQ_16 => "FREQ 16-bit",
+        BitFlags::CMD_FREQ_DEFER => "FREQ defer",
+        BitFlags::CMD_SELECT => "SELECT",
+        BitFlags::CMD_SYNC_SELSRC => "SYNC/SELSRC",
+        BitFlags::CMD_SLEEP_RESET_CLR => "SLEEP/RESET/CLR",
+        _ => "unknown",
+    }
+}
@@ -1,48 +1,61 @@
-use embedded_hal::spi::blocking::{SpiBus, SpiDevice};
+use crate::{
+    marker, Ad983x, BitFlags, Config, Error, OutputWaveform, SquareWave, SquareWavePin, Variant,
+    WriteWord,
+};
 
-use crate::{marker, Ad983x, BitFlags, Error, OutputWaveform};
+impl Config {
+    pub(crate) fn with_ad9833_waveform(self, waveform: OutputWaveform) -> Self {
+        match waveform {
+            OutputWaveform::Sinusoidal => self.with_low(BitFlags::OPBITEN).with_low(BitFlags::MODE),
+            OutputWaveform::Triangle => self.with_low(BitFlags::OPBITEN).with_high(BitFlags::MODE),
+            OutputWaveform::SquareMsbOfDac => self
+                .with_high(BitFlags::OPBITEN)
+                .with_low(BitFlags::MODE)
+                .with_high(BitFlags::DIV2),
+            OutputWaveform::SquareMsbOfDacDiv2 => self
+                .with_high(BitFlags::OPBITEN)
+                .with_low(BitFlags::MODE)
+                .with_low(BitFlags::DIV2),
+        }
+    }
+}
 
 impl<DEV, E> Ad983x<DEV, marker::Ad9833Ad9837>
 where
-    DEV: SpiDevice<Error = E>,
-    DEV::Bus: SpiBus,
+    DEV: WriteWord<Error = E>,
 {
     /// Create a new instance of an AD9833 device.
     ///
     /// Remember to call `reset()` before using the device after power up.
     pub fn new_ad9833(spi: DEV) -> Self {
-        Self::create(spi)
+        Self::create(spi, Variant::Ad9833)
     }
     /// Create a new instance of an AD9837 device.
     ///
     /// Remember to call `reset()` before using the device after power up.
     pub fn new_ad9837(spi: DEV) -> Self {
-        // Behaves the same as AD9833
-        Self::create(spi)
+        Self::create(spi, Variant::Ad9837)
     }
 
     /// Set the output waveform
     pub fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Error<E>> {
-        let control = match waveform {
-            OutputWaveform::Sinusoidal => self
-                .control
-                .with_low(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE),
-            OutputWaveform::Triangle => self
-                .control
-                .with_low(BitFlags::OPBITEN)
-                .with_high(BitFlags::MODE),
-            OutputWaveform::SquareMsbOfDac => self
-                .control
-                .with_high(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE)
-                .with_high(BitFlags::DIV2),
-            OutputWaveform::SquareMsbOfDacDiv2 => self
-                .control
-                .with_high(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE)
-                .with_low(BitFlags::DIV2),
-        };
+        let control = self.control.with_ad9833_waveform(waveform);
+        trace_op!("set_output_waveform {:?}", waveform);
         self.write_control(control)
     }
+
+    /// Output a square wave
+    ///
+    /// The square wave is output on the VOUT pin.
+    pub fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Error<E>> {
+        let waveform = match square_wave {
+            SquareWave::MsbOfDac => OutputWaveform::SquareMsbOfDac,
+            SquareWave::MsbOfDacDiv2 => OutputWaveform::SquareMsbOfDacDiv2,
+        };
+        self.set_output_waveform(waveform)?;
+        Ok(SquareWavePin::Vout)
+    }
 }
@@ -1,79 +1,115 @@
-use embedded_hal::spi::blocking::{SpiBus, SpiDevice};
+use crate::{
+    marker, Ad983x, BitFlags, Config, ControlSource, Error, OutputWaveform, SignBitOutput,
+    SquareWave, SquareWavePin, Variant, WriteWord,
+};
 
-use crate::{marker, Ad983x, BitFlags, ControlSource, Error, OutputWaveform, SignBitOutput};
+impl Config {
+    /// Returns `None` for the square waveforms, which are not available on the VOUT pin.
+    pub(crate) fn with_ad9834_waveform(self, waveform: OutputWaveform) -> Option<Self> {
+        match waveform {
+            OutputWaveform::Sinusoidal => {
+                Some(self.with_low(BitFlags::OPBITEN).with_low(BitFlags::MODE))
+            }
+            OutputWaveform::Triangle => {
+                Some(self.with_low(BitFlags::OPBITEN).with_high(BitFlags::MODE))
+            }
+            OutputWaveform::SquareMsbOfDac | OutputWaveform::SquareMsbOfDacDiv2 => None,
+        }
+    }
+
+    pub(crate) fn with_sign_bit_output(self, configuration: SignBitOutput) -> Self {
+        match configuration {
+            SignBitOutput::Disabled => self.with_low(BitFlags::OPBITEN),
+            SignBitOutput::Comparator => self
+                .with_high(BitFlags::OPBITEN)
+                .with_low(BitFlags::MODE)
+                .with_high(BitFlags::SIGN_PIB)
+                .with_high(BitFlags::DIV2),
+            SignBitOutput::SquareMsbOfDac => self
+                .with_high(BitFlags::OPBITEN)
+                .with_low(BitFlags::MODE)
+                .with_low(BitFlags::SIGN_PIB)
+                .with_high(BitFlags::DIV2),
+            SignBitOutput::SquareMsbOfDacDiv2 => self
+                .with_high(BitFlags::OPBITEN)
+                .with_low(BitFlags::MODE)
+                .with_low(BitFlags::SIGN_PIB)
+                .with_low(BitFlags::DIV2),
+        }
+    }
+
+    pub(crate) fn with_control_source(self, source: ControlSource) -> Self {
+        match source {
+            ControlSource::Software => self.with_low(BitFlags::PIN_SW),
+            ControlSource::HardwarePins => self.with_high(BitFlags::PIN_SW),
+        }
+    }
+}
 
 impl<DEV, E> Ad983x<DEV, marker::Ad9834Ad9838>
 where
-    DEV: SpiDevice<Error = E>,
-    DEV::Bus: SpiBus,
+    DEV: WriteWord<Error = E>,
 {
     /// Create a new instance of an AD9834 device.
     /// Remember to call `reset()` before using the device after power up.
     pub fn new_ad9834(spi: DEV) -> Self {
-        Self::create(spi)
+        Self::create(spi, Variant::Ad9834)
     }
 
     /// Create a new instance of an AD9838 device.
     /// Remember to call `reset()` before using the device after power up.
     pub fn new_ad9838(spi: DEV) -> Self {
-        Self::create(spi)
+        Self::create(spi, Variant::Ad9838)
     }
 
     /// Set the output waveform
     ///
-    /// Will return `Error::InvalidArgument` for `SquareMsbOfDac` and `SquareMsbOfDacDiv2`
+    /// Will return `Error::UnsupportedWaveform` for `SquareMsbOfDac` and `SquareMsbOfDacDiv2`
     /// as this is not available on AD9834/AD9838 devices. To set the digital output,
     /// please use
     pub fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Error<E>> {
-        let control = match waveform {
-            OutputWaveform::Sinusoidal => self
-                .control
-                .with_low(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE),
-            OutputWaveform::Triangle => self
-                .control
-                .with_low(BitFlags::OPBITEN)
-                .with_high(BitFlags::MODE),
-            OutputWaveform::SquareMsbOfDac => return Err(Error::InvalidArgument),
-            OutputWaveform::SquareMsbOfDacDiv2 => return Err(Error::InvalidArgument),
-        };
+        let control = self
+            .control
+            .with_ad9834_waveform(waveform)
+            .ok_or(Error::UnsupportedWaveform(waveform))?;
+        trace_op!("set_output_waveform {:?}", waveform);
         self.write_control(control)
     }
 
     /// Set the digital output
+    ///
+    /// Enabling the output replaces a triangle output on the VOUT pin by a
+    /// sine wave, as the triangle output cannot be used together with it.
     pub fn set_sign_bit_output(&mut self, configuration: SignBitOutput) -> Result<(), Error<E>> {
-        let control = match configuration {
-            SignBitOutput::Disabled => self.control.with_low(BitFlags::OPBITEN),
-            SignBitOutput::Comparator => self
-                .control
-                .with_high(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE)
-                .with_high(BitFlags::SIGN_PIB)
-                .with_high(BitFlags::DIV2),
-            SignBitOutput::SquareMsbOfDac => self
-                .control
-                .with_high(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE)
-                .with_low(BitFlags::SIGN_PIB)
-                .with_high(BitFlags::DIV2),
-            SignBitOutput::SquareMsbOfDacDiv2 => self
-                .control
-                .with_high(BitFlags::OPBITEN)
-                .with_low(BitFlags::MODE)
-                .with_low(BitFlags::SIGN_PIB)
-                .with_low(BitFlags::DIV2),
-        };
+        let control = self.control.with_sign_bit_output(configuration);
+        trace_op!("set_sign_bit_output {:?}", configuration);
         self.write_control(control)
     }
 
+    /// Output a square wave
+    ///
+    /// The square wave is output on the SIGN BIT OUT pin.
+    /// A sine wave output on the VOUT pin is kept, but a triangle output is
+    /// replaced by a sine wave, as it cannot be used together with the
+    /// SIGN BIT OUT pin.
+    pub fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Error<E>> {
+        let configuration = match square_wave {
+            SquareWave::MsbOfDac => SignBitOutput::SquareMsbOfDac,
+            SquareWave::MsbOfDacDiv2 => SignBitOutput::SquareMsbOfDacDiv2,
+        };
+        self.set_sign_bit_output(configuration)?;
+        Ok(SquareWavePin::SignBitOut)
+    }
+
     /// Set the control source used for the functions:
     /// frequency register selection, phase register selection,
     /// reset of internal registers, and DAC power-down.
     pub fn set_control_source(&mut self, source: ControlSource) -> Result<(), Error<E>> {
-        let control = match source {
-            ControlSource::Software => self.control.with_low(BitFlags::PIN_SW),
-            ControlSource::HardwarePins => self.control.with_high(BitFlags::PIN_SW),
-        };
+        let control = self.control.with_control_source(source);
+        trace_op!("set_control_source {:?}", source);
         self.write_control(control)
     }
 }
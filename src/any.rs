@@ -0,0 +1,380 @@
+use crate::{
+    marker, Ad983x, Capabilities, ControlSource, ControlWord, Dds, Error, FrequencyRegister,
+    OutputWaveform, PhaseRegister, PoweredDown, RetryPolicy, SignBitOutput, SquareWave,
+    SquareWavePin, Variant, WriteWord,
+};
+
+/// Device whose variant is selected at runtime
+///
+/// Operations that are not available on the selected variant
+/// return `Error::Unsupported`.
+#[derive(Debug)]
+pub enum AnyAd983x<DEV> {
+    /// AD9833 device
+    Ad9833(Ad983x<DEV, marker::Ad9833Ad9837>),
+    /// AD9834 device
+    Ad9834(Ad983x<DEV, marker::Ad9834Ad9838>),
+    /// AD9837 device
+    Ad9837(Ad983x<DEV, marker::Ad9833Ad9837>),
+    /// AD9838 device
+    Ad9838(Ad983x<DEV, marker::Ad9834Ad9838>),
+    /// AD9832 device
+    Ad9832(Ad983x<DEV, marker::Ad9832Ad9835>),
+    /// AD9835 device
+    Ad9835(Ad983x<DEV, marker::Ad9832Ad9835>),
+    /// AD5930 device
+    Ad5930(Ad983x<DEV, marker::Ad5930Ad5932>),
+    /// AD5932 device
+    Ad5932(Ad983x<DEV, marker::Ad5930Ad5932>),
+}
+
+macro_rules! dispatch {
+    ($self:ident, $dev:ident => $body:expr) => {
+        match $self {
+            AnyAd983x::Ad9833($dev) | AnyAd983x::Ad9837($dev) => $body,
+            AnyAd983x::Ad9834($dev) | AnyAd983x::Ad9838($dev) => $body,
+            AnyAd983x::Ad9832($dev) | AnyAd983x::Ad9835($dev) => $body,
+            AnyAd983x::Ad5930($dev) | AnyAd983x::Ad5932($dev) => $body,
+        }
+    };
+}
+
+/// Dispatch an operation that is not available on AD5930/AD5932 devices.
+macro_rules! dispatch_dds {
+    ($self:ident, $dev:ident => $body:expr) => {
+        match $self {
+            AnyAd983x::Ad9833($dev) | AnyAd983x::Ad9837($dev) => $body,
+            AnyAd983x::Ad9834($dev) | AnyAd983x::Ad9838($dev) => $body,
+            AnyAd983x::Ad9832($dev) | AnyAd983x::Ad9835($dev) => $body,
+            AnyAd983x::Ad5930(_) | AnyAd983x::Ad5932(_) => Err(Error::Unsupported),
+        }
+    };
+}
+
+impl<DEV> AnyAd983x<DEV> {
+    /// Create a new instance of the given device variant.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new(spi: DEV, variant: Variant) -> Self {
+        match variant {
+            Variant::Ad9833 => AnyAd983x::Ad9833(Ad983x::create(spi, variant)),
+            Variant::Ad9834 => AnyAd983x::Ad9834(Ad983x::create(spi, variant)),
+            Variant::Ad9837 => AnyAd983x::Ad9837(Ad983x::create(spi, variant)),
+            Variant::Ad9838 => AnyAd983x::Ad9838(Ad983x::create(spi, variant)),
+            Variant::Ad9832 => AnyAd983x::Ad9832(Ad983x::create_ad9832_ad9835(spi, variant)),
+            Variant::Ad9835 => AnyAd983x::Ad9835(Ad983x::create_ad9832_ad9835(spi, variant)),
+            Variant::Ad5930 => AnyAd983x::Ad5930(Ad983x::create_ad5930_ad5932(spi, variant)),
+            Variant::Ad5932 => AnyAd983x::Ad5932(Ad983x::create_ad5930_ad5932(spi, variant)),
+        }
+    }
+
+    /// Get the device variant
+    pub fn variant(&self) -> Variant {
+        dispatch!(self, dev => dev.variant())
+    }
+
+    /// Get the device capabilities and limits
+    pub fn capabilities(&self) -> Capabilities {
+        dispatch!(self, dev => dev.capabilities())
+    }
+
+    /// Get the configured master clock (MCLK) frequency in Hz, if any
+    pub fn mclk_frequency(&self) -> Option<u32> {
+        dispatch!(self, dev => dev.mclk_frequency())
+    }
+
+    /// Whether the device state may differ from the driver state.
+    ///
+    /// This is the case after an SPI communication error until the control
+    /// register has been written again.
+    pub fn is_desynchronized(&self) -> bool {
+        dispatch!(self, dev => dev.is_desynchronized())
+    }
+
+    /// Set the retry policy for SPI communication errors
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        dispatch!(self, dev => dev.set_retry_policy(policy))
+    }
+
+    /// Get the retry policy for SPI communication errors
+    pub fn retry_policy(&self) -> RetryPolicy {
+        dispatch!(self, dev => dev.retry_policy())
+    }
+
+    /// Get the number of retries that happened since the creation of the driver
+    pub fn retry_count(&self) -> u32 {
+        dispatch!(self, dev => dev.retry_count())
+    }
+
+    /// Destroy driver instance, return SPI bus instance and CS output pin.
+    pub fn destroy(self) -> DEV {
+        dispatch!(self, dev => dev.destroy())
+    }
+}
+
+impl<DEV, E> AnyAd983x<DEV>
+where
+    DEV: WriteWord<Error = E>,
+{
+    /// Resets the internal registers and leaves the device disabled.
+    ///
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn reset(&mut self) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.reset())
+    }
+
+    /// Disable the device (enable reset)
+    ///
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn disable(&mut self) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.disable())
+    }
+
+    /// Enable the device (disable reset)
+    ///
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn enable(&mut self) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.enable())
+    }
+
+    /// Set the frequency register value
+    ///
+    /// The value is a 28-bit word on AD9833/AD9834/AD9837/AD9838 devices and
+    /// a 32-bit word on AD9832/AD9835 devices.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit
+    /// in the frequency register or `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn set_frequency(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.set_frequency(register, value))
+    }
+
+    /// Write the control register with the current driver state.
+    ///
+    /// This brings the device back in sync after an SPI communication error.
+    pub fn resync(&mut self) -> Result<(), Error<E>> {
+        dispatch!(self, dev => dev.resync())
+    }
+
+    /// Set the master clock (MCLK) frequency in Hz
+    ///
+    /// Returns `Error::InvalidMclkFrequency` if the frequency exceeds the maximum
+    /// MCLK frequency of the device.
+    pub fn set_mclk_frequency(&mut self, mclk_hz: u32) -> Result<(), Error<E>> {
+        dispatch!(self, dev => dev.set_mclk_frequency(mclk_hz))
+    }
+
+    /// Set the frequency in Hz
+    ///
+    /// Returns `Error::MclkFrequencyNotSet` if no MCLK frequency has been configured or
+    /// `Error::FrequencyOutOfRange` if the frequency is negative or exceeds half
+    /// the MCLK frequency.
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn set_frequency_hz(
+        &mut self,
+        register: FrequencyRegister,
+        frequency_hz: f64,
+    ) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.set_frequency_hz(register, frequency_hz))
+    }
+
+    /// Get the control register value as known by the driver
+    ///
+    /// Returns `None` on AD9832/AD9835/AD5930/AD5932 devices.
+    pub fn control_word(&self) -> Option<ControlWord> {
+        match self {
+            AnyAd983x::Ad9833(dev) | AnyAd983x::Ad9837(dev) => Some(dev.control_word()),
+            AnyAd983x::Ad9834(dev) | AnyAd983x::Ad9838(dev) => Some(dev.control_word()),
+            _ => None,
+        }
+    }
+
+    /// Write the control register value.
+    ///
+    /// Returns `Error::Unsupported` on AD9832/AD9835/AD5930/AD5932 devices
+    /// and if setting PIN/SW or SIGN/PIB on AD9833/AD9837 devices.
+    /// Returns `Error::UnsupportedWaveform` if setting MODE together with OPBITEN.
+    pub fn write_control_word(&mut self, word: ControlWord) -> Result<(), Error<E>> {
+        match self {
+            AnyAd983x::Ad9833(dev) | AnyAd983x::Ad9837(dev) => dev.write_control_word(word),
+            AnyAd983x::Ad9834(dev) | AnyAd983x::Ad9838(dev) => dev.write_control_word(word),
+            _ => Err(Error::Unsupported),
+        }
+    }
+
+    /// Set the frequency 14-bit MSBs
+    ///
+    /// Returns `Error::Unsupported` on AD9832/AD9835/AD5930/AD5932 devices.
+    pub fn set_frequency_msb(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+    ) -> Result<(), Error<E>> {
+        match self {
+            AnyAd983x::Ad9833(dev) | AnyAd983x::Ad9837(dev) => {
+                dev.set_frequency_msb(register, value)
+            }
+            AnyAd983x::Ad9834(dev) | AnyAd983x::Ad9838(dev) => {
+                dev.set_frequency_msb(register, value)
+            }
+            _ => Err(Error::Unsupported),
+        }
+    }
+
+    /// Set the frequency 14-bit LSBs
+    ///
+    /// Returns `Error::Unsupported` on AD9832/AD9835/AD5930/AD5932 devices.
+    pub fn set_frequency_lsb(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+    ) -> Result<(), Error<E>> {
+        match self {
+            AnyAd983x::Ad9833(dev) | AnyAd983x::Ad9837(dev) => {
+                dev.set_frequency_lsb(register, value)
+            }
+            AnyAd983x::Ad9834(dev) | AnyAd983x::Ad9838(dev) => {
+                dev.set_frequency_lsb(register, value)
+            }
+            _ => Err(Error::Unsupported),
+        }
+    }
+
+    /// Select the frequency register that is used
+    ///
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.select_frequency(register))
+    }
+
+    /// Set a phase register (12-bit value)
+    ///
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn set_phase(&mut self, register: PhaseRegister, value: u16) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.set_phase(register, value))
+    }
+
+    /// Select the phase register that is used.
+    ///
+    /// Returns `Error::Unsupported` on AD5930/AD5932 devices.
+    pub fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Error<E>> {
+        dispatch_dds!(self, dev => dev.select_phase(register))
+    }
+
+    /// Set device parts powered-down state.
+    pub fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Error<E>> {
+        dispatch!(self, dev => dev.set_powered_down(config))
+    }
+
+    /// Set the output waveform
+    ///
+    /// Will return `Error::UnsupportedWaveform` for `SquareMsbOfDac` and `SquareMsbOfDacDiv2`
+    /// on AD9834/AD9838 devices.
+    /// Only `Sinusoidal` is available on AD9832/AD9835 devices. Other waveforms
+    /// return `Error::UnsupportedWaveform` there.
+    /// Square waveforms return `Error::UnsupportedWaveform` on AD5930/AD5932 devices.
+    pub fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Error<E>> {
+        dispatch!(self, dev => dev.set_output_waveform(waveform))
+    }
+
+    /// Output a square wave
+    ///
+    /// Returns the pin where the square wave is available.
+    /// Returns `Error::Unsupported` on AD9832/AD9835 devices.
+    /// Only `SquareWave::MsbOfDac` is available on AD5930/AD5932 devices.
+    pub fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Error<E>> {
+        dispatch!(self, dev => dev.set_square_wave_output(square_wave))
+    }
+
+    /// Output pin where the device can provide a square wave, if any
+    pub fn square_wave_pin(&self) -> Option<SquareWavePin> {
+        self.capabilities().square_wave_pin
+    }
+
+    /// Set the digital output (only available on AD9834/AD9838 devices)
+    ///
+    /// Returns `Error::Unsupported` on other devices.
+    pub fn set_sign_bit_output(&mut self, configuration: SignBitOutput) -> Result<(), Error<E>> {
+        match self {
+            AnyAd983x::Ad9834(dev) | AnyAd983x::Ad9838(dev) => {
+                dev.set_sign_bit_output(configuration)
+            }
+            _ => Err(Error::Unsupported),
+        }
+    }
+
+    /// Set the control source (only available on AD9834/AD9838/AD9832/AD9835 devices)
+    ///
+    /// Returns `Error::Unsupported` on other devices.
+    pub fn set_control_source(&mut self, source: ControlSource) -> Result<(), Error<E>> {
+        match self {
+            AnyAd983x::Ad9833(_) | AnyAd983x::Ad9837(_) => Err(Error::Unsupported),
+            AnyAd983x::Ad9834(dev) | AnyAd983x::Ad9838(dev) => dev.set_control_source(source),
+            AnyAd983x::Ad9832(dev) | AnyAd983x::Ad9835(dev) => dev.set_control_source(source),
+            AnyAd983x::Ad5930(_) | AnyAd983x::Ad5932(_) => Err(Error::Unsupported),
+        }
+    }
+}
+
+impl<DEV, E> Dds for AnyAd983x<DEV>
+where
+    DEV: WriteWord<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        AnyAd983x::reset(self)
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        AnyAd983x::disable(self)
+    }
+
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        AnyAd983x::enable(self)
+    }
+
+    fn set_frequency(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<(), Self::Error> {
+        AnyAd983x::set_frequency(self, register, value)
+    }
+
+    fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Self::Error> {
+        AnyAd983x::select_frequency(self, register)
+    }
+
+    fn set_phase(&mut self, register: PhaseRegister, value: u16) -> Result<(), Self::Error> {
+        AnyAd983x::set_phase(self, register, value)
+    }
+
+    fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Self::Error> {
+        AnyAd983x::select_phase(self, register)
+    }
+
+    fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Self::Error> {
+        AnyAd983x::set_powered_down(self, config)
+    }
+
+    fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Self::Error> {
+        AnyAd983x::set_output_waveform(self, waveform)
+    }
+
+    fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Self::Error> {
+        AnyAd983x::set_square_wave_output(self, square_wave)
+    }
+
+    fn square_wave_pin(&self) -> Option<SquareWavePin> {
+        AnyAd983x::square_wave_pin(self)
+    }
+}
@@ -0,0 +1,427 @@
+//! Asynchronous driver
+//!
+//! [`Ad983xAsync`] provides the operations of the AD9833/AD9834/AD9837/AD9838
+//! devices on top of an `embedded_hal_async::spi::SpiDevice` so that
+//! an executor is not blocked while the words are sent. The words are
+//! encoded like in the blocking driver.
+//!
+//! Retry policies are not available on this driver. After an SPI
+//! communication error, or if an operation future is dropped before it
+//! completes, the control register is written again on the next operation
+//! or through `resync()`.
+//!
+//! Sweeps, FSK keying and tone sequences that wait asynchronously between
+//! the steps are available in the [`modulation`] module.
+//!
+//! This module is available with the `async` feature, which requires Rust 1.75.
+//!
+//! ```
+//! use ad983x::{asynch::Ad983xAsync, FrequencyRegister, OutputWaveform};
+//! use embedded_hal_async::spi::SpiDevice;
+//!
+//! async fn configure<DEV: SpiDevice>(spi: DEV) -> Result<(), ad983x::Error<DEV::Error>> {
+//!     let mut dds = Ad983xAsync::new_ad9833(spi);
+//!     dds.reset().await?;
+//!     dds.set_frequency(FrequencyRegister::F0, 4724).await?;
+//!     dds.set_output_waveform(OutputWaveform::Triangle).await?;
+//!     dds.enable().await
+//! }
+//! ```
+
+use core::marker::PhantomData;
+use embedded_hal_async::spi::SpiDevice;
+
+pub mod modulation;
+
+#[cfg(any(feature = "log", feature = "defmt"))]
+use crate::common::{register_data, register_name};
+use crate::{
+    common::{
+        check_control_word, check_mclk_frequency, check_value_fits, frequency_register_value,
+    },
+    encoder, marker, private, BitFlags, Capabilities, Config, ControlSource, ControlWord, Error,
+    FrequencyRegister, OutputWaveform, PhaseRegister, PoweredDown, SignBitOutput, SquareWave,
+    SquareWavePin, Variant,
+};
+
+/// Asynchronous AD9833/AD9834/AD9837/AD9838 direct digital synthesizer
+#[derive(Debug)]
+pub struct Ad983xAsync<DEV, IC> {
+    spi: DEV,
+    control: Config,
+    variant: Variant,
+    mclk_hz: Option<u32>,
+    desynchronized: bool,
+    _ic: PhantomData<IC>,
+}
+
+impl<DEV, IC> Ad983xAsync<DEV, IC> {
+    fn create(spi: DEV, variant: Variant) -> Self {
+        Ad983xAsync {
+            spi,
+            control: Config {
+                bits: BitFlags::RESET,
+            },
+            variant,
+            mclk_hz: None,
+            desynchronized: false,
+            _ic: PhantomData,
+        }
+    }
+
+    /// Get the device variant
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Get the device capabilities and limits
+    pub fn capabilities(&self) -> Capabilities {
+        self.variant.capabilities()
+    }
+
+    /// Get the configured master clock (MCLK) frequency in Hz, if any
+    pub fn mclk_frequency(&self) -> Option<u32> {
+        self.mclk_hz
+    }
+
+    /// Whether the device state may differ from the driver state.
+    ///
+    /// This is the case after an SPI communication error or after dropping
+    /// an unfinished operation until the control register has been written again.
+    pub fn is_desynchronized(&self) -> bool {
+        self.desynchronized
+    }
+
+    /// Get the control register value as known by the driver
+    pub fn control_word(&self) -> ControlWord {
+        ControlWord {
+            bits: self.control.bits,
+        }
+    }
+
+    /// Destroy driver instance, return SPI device instance.
+    pub fn destroy(self) -> DEV {
+        self.spi
+    }
+}
+
+impl<DEV, IC, E> Ad983xAsync<DEV, IC>
+where
+    DEV: SpiDevice<Error = E>,
+    IC: private::Ad983xFamily,
+{
+    /// Set the master clock (MCLK) frequency in Hz
+    ///
+    /// This is only used to convert frequencies in Hz into frequency register values.
+    /// Returns `Error::InvalidMclkFrequency` if the frequency exceeds the maximum
+    /// MCLK frequency of the device.
+    pub fn set_mclk_frequency(&mut self, mclk_hz: u32) -> Result<(), Error<E>> {
+        check_mclk_frequency(self.variant, mclk_hz)?;
+        self.mclk_hz = Some(mclk_hz);
+        Ok(())
+    }
+
+    /// Resets the internal registers and leaves the device disabled.
+    pub async fn reset(&mut self) -> Result<(), Error<E>> {
+        self.disable().await
+    }
+
+    /// Disable the device (enable reset)
+    ///
+    /// This resets the internal registers.
+    pub async fn disable(&mut self) -> Result<(), Error<E>> {
+        trace_op!("disable");
+        let control = self.control.with_high(BitFlags::RESET);
+        self.write_control(control).await
+    }
+
+    /// Enable the device (disable reset)
+    pub async fn enable(&mut self) -> Result<(), Error<E>> {
+        trace_op!("enable");
+        let control = self.control.with_low(BitFlags::RESET);
+        self.write_control(control).await
+    }
+
+    /// Set the frequency as a 28-bit word
+    ///
+    /// This will change the mode to 28-bit if it is not used.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 28 bits.
+    pub async fn set_frequency(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<(), Error<E>> {
+        check_value_fits(value, 28)?;
+        trace_op!("set_frequency {:?} = {:#x}", register, value);
+        let control = self.control.with_high(BitFlags::B28);
+        self.write_control_if_different(control).await?;
+        self.write_sequence(&encoder::frequency_words(register, value))
+            .await
+    }
+
+    /// Set the frequency in Hz
+    ///
+    /// Returns `Error::MclkFrequencyNotSet` if no MCLK frequency has been configured or
+    /// `Error::FrequencyOutOfRange` if the frequency is negative or exceeds half
+    /// the MCLK frequency.
+    pub async fn set_frequency_hz(
+        &mut self,
+        register: FrequencyRegister,
+        frequency_hz: f64,
+    ) -> Result<(), Error<E>> {
+        let value = frequency_register_value(self.variant, self.mclk_hz, frequency_hz)?;
+        self.set_frequency(register, value).await
+    }
+
+    /// Set the frequency 14-bit MSBs
+    ///
+    /// This will deactivate the 28-bit mode if it is not already the case.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 14 bits.
+    pub async fn set_frequency_msb(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+    ) -> Result<(), Error<E>> {
+        check_value_fits(value, 14)?;
+        trace_op!("set_frequency_msb {:?} = {:#x}", register, value);
+        let control = self
+            .control
+            .with_low(BitFlags::B28)
+            .with_high(BitFlags::HLB);
+        self.write_control_if_different(control).await?;
+        self.write_sequence(&[encoder::frequency_half_word(register, value)])
+            .await
+    }
+
+    /// Set the frequency 14-bit LSBs
+    ///
+    /// This will deactivate the 28-bit mode if it is not already the case.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 14 bits.
+    pub async fn set_frequency_lsb(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+    ) -> Result<(), Error<E>> {
+        check_value_fits(value, 14)?;
+        trace_op!("set_frequency_lsb {:?} = {:#x}", register, value);
+        let control = self.control.with_low(BitFlags::B28).with_low(BitFlags::HLB);
+        self.write_control_if_different(control).await?;
+        self.write_sequence(&[encoder::frequency_half_word(register, value)])
+            .await
+    }
+
+    /// Select the frequency register that is used
+    pub async fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Error<E>> {
+        trace_op!("select_frequency {:?}", register);
+        let control = self.control.with_frequency_select(register);
+        self.write_control(control).await
+    }
+
+    /// Set a phase register (12-bit value)
+    ///
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 12 bits.
+    pub async fn set_phase(&mut self, register: PhaseRegister, value: u16) -> Result<(), Error<E>> {
+        check_value_fits(value, 12)?;
+        trace_op!("set_phase {:?} = {}", register, value);
+        self.write_sequence(&[encoder::phase_word(register, value)])
+            .await
+    }
+
+    /// Select the phase register that is used.
+    pub async fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Error<E>> {
+        trace_op!("select_phase {:?}", register);
+        let control = self.control.with_phase_select(register);
+        self.write_control(control).await
+    }
+
+    /// Set device parts powered-down state.
+    pub async fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Error<E>> {
+        trace_op!("set_powered_down {:?}", config);
+        let control = self.control.with_powered_down(config);
+        self.write_control(control).await
+    }
+
+    /// Write the control register value.
+    ///
+    /// Returns `Error::Unsupported` if setting PIN/SW or SIGN/PIB on
+    /// AD9833/AD9837 devices.
+    /// Returns `Error::UnsupportedWaveform` with `OutputWaveform::Triangle` if
+    /// setting MODE together with OPBITEN, as the triangle output cannot be
+    /// used together with the digital output.
+    pub async fn write_control_word(&mut self, word: ControlWord) -> Result<(), Error<E>> {
+        check_control_word(self.variant, word)?;
+        trace_op!("write_control_word {:#06x}", word.bits);
+        self.write_control(Config { bits: word.bits }).await
+    }
+
+    /// Write the control register with the current driver state.
+    ///
+    /// This brings the device back in sync after an SPI communication error.
+    pub async fn resync(&mut self) -> Result<(), Error<E>> {
+        trace_op!("resync");
+        let control = self.control;
+        self.write_control(control).await
+    }
+
+    async fn write_control_if_different(&mut self, control: Config) -> Result<(), Error<E>> {
+        if control != self.control || self.desynchronized {
+            self.write_control(control).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn write_control(&mut self, control: Config) -> Result<(), Error<E>> {
+        let payload = encoder::control_payload(control.bits);
+        trace_op!("control {:#06x} -> {:#06x}", self.control.bits, payload);
+        self.write_word(payload).await?;
+        self.control = control;
+        self.desynchronized = false;
+        Ok(())
+    }
+
+    /// Write words to registers other than the control register.
+    ///
+    /// The control register is written first if its state is unknown.
+    async fn write_sequence(&mut self, words: &[u16]) -> Result<(), Error<E>> {
+        if self.desynchronized {
+            self.resync().await?;
+        }
+        for word in words {
+            self.write_word(*word).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_word(&mut self, payload: u16) -> Result<(), Error<E>> {
+        trace_word!(
+            "word {:#06x}: {} = {:#x}",
+            payload,
+            register_name(payload),
+            register_data(payload)
+        );
+        // Set until the word has been sent so that dropping the future in
+        // the middle of a write is handled like an SPI communication error.
+        self.desynchronized = true;
+        self.spi
+            .write(&payload.to_be_bytes())
+            .await
+            .map_err(Error::Spi)?;
+        self.desynchronized = false;
+        Ok(())
+    }
+}
+
+impl<DEV, E> Ad983xAsync<DEV, marker::Ad9833Ad9837>
+where
+    DEV: SpiDevice<Error = E>,
+{
+    /// Create a new instance of an AD9833 device.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new_ad9833(spi: DEV) -> Self {
+        Self::create(spi, Variant::Ad9833)
+    }
+
+    /// Create a new instance of an AD9837 device.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new_ad9837(spi: DEV) -> Self {
+        Self::create(spi, Variant::Ad9837)
+    }
+
+    /// Set the output waveform
+    pub async fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Error<E>> {
+        let control = self.control.with_ad9833_waveform(waveform);
+        trace_op!("set_output_waveform {:?}", waveform);
+        self.write_control(control).await
+    }
+
+    /// Output a square wave
+    ///
+    /// The square wave is output on the VOUT pin instead of the configured
+    /// output waveform.
+    pub async fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Error<E>> {
+        let waveform = match square_wave {
+            SquareWave::MsbOfDac => OutputWaveform::SquareMsbOfDac,
+            SquareWave::MsbOfDacDiv2 => OutputWaveform::SquareMsbOfDacDiv2,
+        };
+        self.set_output_waveform(waveform).await?;
+        Ok(SquareWavePin::Vout)
+    }
+}
+
+impl<DEV, E> Ad983xAsync<DEV, marker::Ad9834Ad9838>
+where
+    DEV: SpiDevice<Error = E>,
+{
+    /// Create a new instance of an AD9834 device.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new_ad9834(spi: DEV) -> Self {
+        Self::create(spi, Variant::Ad9834)
+    }
+
+    /// Create a new instance of an AD9838 device.
+    ///
+    /// Remember to call `reset()` before using the device after power up.
+    pub fn new_ad9838(spi: DEV) -> Self {
+        Self::create(spi, Variant::Ad9838)
+    }
+
+    /// Set the output waveform
+    ///
+    /// Will return `Error::UnsupportedWaveform` for `SquareMsbOfDac` and `SquareMsbOfDacDiv2`
+    /// as this is not available on AD9834/AD9838 devices.
+    pub async fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Error<E>> {
+        let control = self
+            .control
+            .with_ad9834_waveform(waveform)
+            .ok_or(Error::UnsupportedWaveform(waveform))?;
+        trace_op!("set_output_waveform {:?}", waveform);
+        self.write_control(control).await
+    }
+
+    /// Set the digital output
+    ///
+    /// Enabling the output replaces a triangle output on the VOUT pin by a
+    /// sine wave, as the triangle output cannot be used together with it.
+    pub async fn set_sign_bit_output(
+        &mut self,
+        configuration: SignBitOutput,
+    ) -> Result<(), Error<E>> {
+        let control = self.control.with_sign_bit_output(configuration);
+        trace_op!("set_sign_bit_output {:?}", configuration);
+        self.write_control(control).await
+    }
+
+    /// Output a square wave
+    ///
+    /// The square wave is output on the SIGN BIT OUT pin.
+    /// A sine wave output on the VOUT pin is kept, but a triangle output is
+    /// replaced by a sine wave, as it cannot be used together with the
+    /// SIGN BIT OUT pin.
+    pub async fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Error<E>> {
+        let configuration = match square_wave {
+            SquareWave::MsbOfDac => SignBitOutput::SquareMsbOfDac,
+            SquareWave::MsbOfDacDiv2 => SignBitOutput::SquareMsbOfDacDiv2,
+        };
+        self.set_sign_bit_output(configuration).await?;
+        Ok(SquareWavePin::SignBitOut)
+    }
+
+    /// Set the control source used for the functions:
+    /// frequency register selection, phase register selection,
+    /// reset of internal registers, and DAC power-down.
+    pub async fn set_control_source(&mut self, source: ControlSource) -> Result<(), Error<E>> {
+        let control = self.control.with_control_source(source);
+        trace_op!("set_control_source {:?}", source);
+        self.write_control(control).await
+    }
+}
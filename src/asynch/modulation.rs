@@ -0,0 +1,288 @@
+//! Asynchronous modulation and sweep engines
+//!
+//! The [`Modulator`] runs frequency sweeps, FSK keying and tone sequences on
+//! an [`Ad983xAsync`] device and awaits an `embedded_hal_async::delay::DelayNs`
+//! between the steps.
+//!
+//! The frequencies are frequency register values. Each new frequency is
+//! written to the frequency register that is not in use and then selected in
+//! a single control register write, so the output switches between complete
+//! frequencies only.
+//!
+//! A running operation can be cancelled in two ways:
+//! - Through a [`StopSignal`]. This is checked before each step. The operation
+//!   then returns [`Outcome::Stopped`] and the device keeps outputting the
+//!   frequency of the last step (the space frequency for FSK keying).
+//! - By dropping the future. The device keeps outputting the frequency of the
+//!   last step that was selected. If the future was dropped in the middle of
+//!   a word, the control register is written again on the next operation.
+//!
+//! ```
+//! use ad983x::asynch::{
+//!     modulation::{Modulator, Outcome, StopSignal, Sweep},
+//!     Ad983xAsync,
+//! };
+//! use embedded_hal_async::{delay::DelayNs, spi::SpiDevice};
+//!
+//! static STOP: StopSignal = StopSignal::new();
+//!
+//! async fn run<DEV: SpiDevice, D: DelayNs>(spi: DEV, delay: D) {
+//!     let mut dds = Ad983xAsync::new_ad9833(spi);
+//!     dds.reset().await.unwrap();
+//!     dds.enable().await.unwrap();
+//!     let mut modulator = Modulator::new(delay).with_stop_signal(&STOP);
+//!     let sweep = Sweep {
+//!         start_frequency: 10_000,
+//!         frequency_increment: 100,
+//!         steps: 50,
+//!         step_us: 1_000,
+//!     };
+//!     // STOP.stop() can be called from another task to end the sweep early.
+//!     match modulator.sweep(&mut dds, &sweep).await.unwrap() {
+//!         Outcome::Completed => (),
+//!         Outcome::Stopped => (),
+//!     }
+//! }
+//! ```
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use embedded_hal_async::{delay::DelayNs, spi::SpiDevice};
+
+use super::Ad983xAsync;
+use crate::{private, BitFlags, Error, FrequencyRegister};
+
+/// Linear frequency sweep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    /// Frequency of the first step (28-bit value)
+    pub start_frequency: u32,
+    /// Frequency increment between the steps
+    ///
+    /// A negative value sweeps down from the start frequency.
+    pub frequency_increment: i32,
+    /// Number of steps, including the first one
+    pub steps: u32,
+    /// Time each step is output in microseconds
+    pub step_us: u32,
+}
+
+/// Binary frequency-shift keying
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fsk {
+    /// Frequency of the 0 bits (28-bit value)
+    ///
+    /// This is written to the F0 register.
+    pub space_frequency: u32,
+    /// Frequency of the 1 bits (28-bit value)
+    ///
+    /// This is written to the F1 register.
+    pub mark_frequency: u32,
+    /// Time each bit is output in microseconds
+    pub bit_us: u32,
+}
+
+/// Tone of a tone sequence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    /// Frequency (28-bit value)
+    pub frequency: u32,
+    /// Time the tone is output in microseconds
+    pub duration_us: u32,
+}
+
+/// How an operation ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// All the steps were output.
+    Completed,
+    /// The operation was stopped through the stop signal.
+    Stopped,
+}
+
+/// Signal to stop a running operation
+///
+/// This can be shared with other tasks or interrupt handlers, for example
+/// as a `static`.
+#[derive(Debug, Default)]
+pub struct StopSignal {
+    stopped: AtomicBool,
+}
+
+impl StopSignal {
+    /// Create a new signal that is not set
+    pub const fn new() -> Self {
+        StopSignal {
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// Request running operations to stop.
+    ///
+    /// Operations started afterwards stop immediately until `reset()` is called.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Release);
+    }
+
+    /// Clear the signal
+    pub fn reset(&self) {
+        self.stopped.store(false, Ordering::Release);
+    }
+
+    /// Whether a stop has been requested
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+}
+
+/// Asynchronous modulation and sweep engine
+#[derive(Debug)]
+pub struct Modulator<'a, D> {
+    delay: D,
+    stop: Option<&'a StopSignal>,
+}
+
+impl<'a, D> Modulator<'a, D>
+where
+    D: DelayNs,
+{
+    /// Create a new engine waiting with the given delay between the steps.
+    pub fn new(delay: D) -> Self {
+        Modulator { delay, stop: None }
+    }
+
+    /// Stop the operations when the signal is set.
+    pub fn with_stop_signal(self, stop: &'a StopSignal) -> Self {
+        Modulator {
+            delay: self.delay,
+            stop: Some(stop),
+        }
+    }
+
+    /// Destroy the engine, return the delay instance.
+    pub fn destroy(self) -> D {
+        self.delay
+    }
+
+    fn is_stopped(&self) -> bool {
+        matches!(self.stop, Some(stop) if stop.is_stopped())
+    }
+
+    /// Run a linear frequency sweep.
+    ///
+    /// Returns `Error::ValueTooWide` before writing anything if any of the
+    /// frequencies does not fit in 28 bits.
+    pub async fn sweep<DEV, IC, E>(
+        &mut self,
+        dds: &mut Ad983xAsync<DEV, IC>,
+        sweep: &Sweep,
+    ) -> Result<Outcome, Error<E>>
+    where
+        DEV: SpiDevice<Error = E>,
+        IC: private::Ad983xFamily,
+    {
+        let last = i64::from(sweep.start_frequency)
+            + i64::from(sweep.frequency_increment) * i64::from(sweep.steps.saturating_sub(1));
+        if !(0..1 << 28).contains(&last) || sweep.start_frequency >= 1 << 28 {
+            return Err(Error::ValueTooWide { bits: 28 });
+        }
+        trace_op!("sweep {} steps", sweep.steps);
+        let mut frequency = i64::from(sweep.start_frequency);
+        for _ in 0..sweep.steps {
+            if self.is_stopped() {
+                return Ok(Outcome::Stopped);
+            }
+            switch_frequency(dds, frequency as u32).await?;
+            self.delay.delay_us(sweep.step_us).await;
+            frequency += i64::from(sweep.frequency_increment);
+        }
+        Ok(Outcome::Completed)
+    }
+
+    /// Key the bits with binary frequency-shift keying.
+    ///
+    /// The frequency registers are written first. The space frequency is
+    /// selected at the end and when stopped.
+    /// Returns `Error::ValueTooWide` if any of the frequencies does not fit in 28 bits.
+    pub async fn fsk<DEV, IC, E, I>(
+        &mut self,
+        dds: &mut Ad983xAsync<DEV, IC>,
+        fsk: &Fsk,
+        bits: I,
+    ) -> Result<Outcome, Error<E>>
+    where
+        DEV: SpiDevice<Error = E>,
+        IC: private::Ad983xFamily,
+        I: IntoIterator<Item = bool>,
+    {
+        if fsk.space_frequency >= 1 << 28 || fsk.mark_frequency >= 1 << 28 {
+            return Err(Error::ValueTooWide { bits: 28 });
+        }
+        trace_op!("fsk");
+        dds.select_frequency(FrequencyRegister::F0).await?;
+        dds.set_frequency(FrequencyRegister::F0, fsk.space_frequency)
+            .await?;
+        dds.set_frequency(FrequencyRegister::F1, fsk.mark_frequency)
+            .await?;
+        let mut outcome = Outcome::Completed;
+        for bit in bits {
+            if self.is_stopped() {
+                outcome = Outcome::Stopped;
+                break;
+            }
+            let register = if bit {
+                FrequencyRegister::F1
+            } else {
+                FrequencyRegister::F0
+            };
+            dds.select_frequency(register).await?;
+            self.delay.delay_us(fsk.bit_us).await;
+        }
+        dds.select_frequency(FrequencyRegister::F0).await?;
+        Ok(outcome)
+    }
+
+    /// Output a sequence of tones.
+    ///
+    /// Returns `Error::ValueTooWide` before writing anything if any of the
+    /// frequencies does not fit in 28 bits.
+    pub async fn tones<DEV, IC, E>(
+        &mut self,
+        dds: &mut Ad983xAsync<DEV, IC>,
+        tones: &[Tone],
+    ) -> Result<Outcome, Error<E>>
+    where
+        DEV: SpiDevice<Error = E>,
+        IC: private::Ad983xFamily,
+    {
+        if tones.iter().any(|tone| tone.frequency >= 1 << 28) {
+            return Err(Error::ValueTooWide { bits: 28 });
+        }
+        trace_op!("tones {}", tones.len());
+        for tone in tones {
+            if self.is_stopped() {
+                return Ok(Outcome::Stopped);
+            }
+            switch_frequency(dds, tone.frequency).await?;
+            self.delay.delay_us(tone.duration_us).await;
+        }
+        Ok(Outcome::Completed)
+    }
+}
+
+/// Write the frequency register that is not in use and select it.
+async fn switch_frequency<DEV, IC, E>(
+    dds: &mut Ad983xAsync<DEV, IC>,
+    value: u32,
+) -> Result<(), Error<E>>
+where
+    DEV: SpiDevice<Error = E>,
+    IC: private::Ad983xFamily,
+{
+    let register = if dds.control.bits & BitFlags::FSELECT != 0 {
+        FrequencyRegister::F0
+    } else {
+        FrequencyRegister::F1
+    };
+    dds.set_frequency(register, value).await?;
+    dds.select_frequency(register).await
+}
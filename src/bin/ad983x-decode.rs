@@ -0,0 +1,80 @@
+//! Decode captured SPI traffic into AD983x commands
+//!
+//! Usage: `ad983x-decode [--variant VARIANT] [--csv] [FILE]`
+//!
+//! The variant is one of ad9832, ad9833, ad9834, ad9835, ad9837, ad9838,
+//! ad5930 and ad5932 (default: ad9833).
+//!
+//! The input is read from standard input if no file is given. Hex dumps are
+//! expected unless `--csv` is given for sigrok/PulseView CSV exports.
+
+use ad983x::decoder::{parse_csv, parse_hex, Decoder};
+use ad983x::Variant;
+use std::io::Read;
+use std::process::exit;
+
+const USAGE: &str = "Usage: ad983x-decode [--variant VARIANT] [--csv] [FILE]
+Variants: ad9832, ad9833, ad9834, ad9835, ad9837, ad9838, ad5930, ad5932 (default: ad9833)";
+
+fn parse_variant(name: &str) -> Option<Variant> {
+    match name.to_ascii_lowercase().as_str() {
+        "ad9832" => Some(Variant::Ad9832),
+        "ad9833" => Some(Variant::Ad9833),
+        "ad9834" => Some(Variant::Ad9834),
+        "ad9835" => Some(Variant::Ad9835),
+        "ad9837" => Some(Variant::Ad9837),
+        "ad9838" => Some(Variant::Ad9838),
+        "ad5930" => Some(Variant::Ad5930),
+        "ad5932" => Some(Variant::Ad5932),
+        _ => None,
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1)
+}
+
+fn main() {
+    let mut variant = Variant::Ad9833;
+    let mut csv = false;
+    let mut path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--variant" => {
+                let name = args.next().unwrap_or_else(|| fail(USAGE));
+                variant = parse_variant(&name)
+                    .unwrap_or_else(|| fail(&format!("Unsupported variant: {}\n{}", name, USAGE)));
+            }
+            "--csv" => csv = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => fail(USAGE),
+        }
+    }
+
+    let input = match &path {
+        Some(path) => std::fs::read_to_string(path),
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map(|_| input)
+        }
+    }
+    .unwrap_or_else(|e| fail(&format!("Error reading input: {}", e)));
+
+    let words = if csv {
+        parse_csv(&input)
+    } else {
+        parse_hex(&input)
+    }
+    .unwrap_or_else(|e| fail(&format!("Error parsing input: {}", e)));
+
+    let mut decoder = Decoder::new(variant);
+    for word in words {
+        println!("{:#06X}  {}", word, decoder.decode(word));
+    }
+}
@@ -0,0 +1,217 @@
+//! Bit-banged 3-wire serial interface
+//!
+//! [`BitBangSpi`] drives the FSYNC, SCLK and SDATA lines of the device
+//! through three output pins and implements `SpiDevice` so it can be given
+//! to the driver in place of a hardware SPI device.
+//!
+//! SCLK idles high and each data bit is set before the falling edge where
+//! the device latches it. The bits are sent MSB first and FSYNC is held low
+//! during each 16-bit word.
+//!
+//! An optional delay can be used to slow down the clock. Otherwise the pins
+//! are toggled as fast as they can be.
+//!
+//! ```no_run
+//! # use embedded_hal::digital::{ErrorType, OutputPin};
+//! # struct Pin;
+//! # impl ErrorType for Pin { type Error = core::convert::Infallible; }
+//! # impl OutputPin for Pin {
+//! #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+//! #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+//! # }
+//! # let (fsync, sclk, sdata) = (Pin, Pin, Pin);
+//! use ad983x::{bitbang::BitBangSpi, Ad983x, FrequencyRegister};
+//!
+//! let spi = BitBangSpi::new(fsync, sclk, sdata);
+//! let mut dds = Ad983x::new_ad9833(spi);
+//! dds.reset().unwrap();
+//! dds.set_frequency(FrequencyRegister::F0, 4724).unwrap();
+//! dds.enable().unwrap();
+//! ```
+
+use core::fmt::Debug;
+use embedded_hal::{
+    delay::DelayNs,
+    digital::OutputPin,
+    spi::{ErrorKind, ErrorType, Operation, SpiDevice},
+};
+
+/// Bit-banged interface error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitBangError<E> {
+    /// Error setting an output pin
+    Pin(E),
+}
+
+impl<E: Debug> embedded_hal::spi::Error for BitBangError<E> {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// Delay that does not wait
+///
+/// This is used when no delay is given to [`BitBangSpi`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Bit-banged FSYNC/SCLK/SDATA interface
+///
+/// This clocks out the bits and frames every 16 bits with FSYNC.
+#[derive(Debug)]
+pub struct BitBangSpi<FSYNC, SCLK, SDATA, D = NoDelay> {
+    fsync: FSYNC,
+    sclk: SCLK,
+    sdata: SDATA,
+    delay: D,
+    half_period_ns: u32,
+    bit_count: u8,
+}
+
+impl<FSYNC, SCLK, SDATA> BitBangSpi<FSYNC, SCLK, SDATA, NoDelay> {
+    /// Create a new interface that toggles the pins as fast as possible.
+    pub fn new(fsync: FSYNC, sclk: SCLK, sdata: SDATA) -> Self {
+        Self::new_with_delay(fsync, sclk, sdata, NoDelay, 0)
+    }
+}
+
+impl<FSYNC, SCLK, SDATA, D> BitBangSpi<FSYNC, SCLK, SDATA, D> {
+    /// Create a new interface that waits `half_period_ns` nanoseconds
+    /// after each SCLK edge.
+    pub fn new_with_delay(
+        fsync: FSYNC,
+        sclk: SCLK,
+        sdata: SDATA,
+        delay: D,
+        half_period_ns: u32,
+    ) -> Self {
+        BitBangSpi {
+            fsync,
+            sclk,
+            sdata,
+            delay,
+            half_period_ns,
+            bit_count: 0,
+        }
+    }
+
+    /// Destroy the interface, return the FSYNC, SCLK and SDATA pins and the delay.
+    pub fn destroy(self) -> (FSYNC, SCLK, SDATA, D) {
+        (self.fsync, self.sclk, self.sdata, self.delay)
+    }
+}
+
+impl<FSYNC, SCLK, SDATA, D, E> BitBangSpi<FSYNC, SCLK, SDATA, D>
+where
+    FSYNC: OutputPin<Error = E>,
+    SCLK: OutputPin<Error = E>,
+    SDATA: OutputPin<Error = E>,
+    D: DelayNs,
+    E: Debug,
+{
+    fn wait(&mut self) {
+        if self.half_period_ns != 0 {
+            self.delay.delay_ns(self.half_period_ns);
+        }
+    }
+
+    fn write_byte(&mut self, byte: u8) -> Result<(), BitBangError<E>> {
+        for bit in (0..8).rev() {
+            if self.bit_count == 0 {
+                self.sclk.set_high().map_err(BitBangError::Pin)?;
+                self.fsync.set_low().map_err(BitBangError::Pin)?;
+            }
+            if byte & (1 << bit) != 0 {
+                self.sdata.set_high().map_err(BitBangError::Pin)?;
+            } else {
+                self.sdata.set_low().map_err(BitBangError::Pin)?;
+            }
+            self.wait();
+            self.sclk.set_low().map_err(BitBangError::Pin)?;
+            self.wait();
+            self.sclk.set_high().map_err(BitBangError::Pin)?;
+            self.bit_count += 1;
+            if self.bit_count == 16 {
+                self.end_word()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), BitBangError<E>> {
+        bytes.iter().try_for_each(|byte| self.write_byte(*byte))
+    }
+
+    fn end_word(&mut self) -> Result<(), BitBangError<E>> {
+        self.bit_count = 0;
+        self.fsync.set_high().map_err(BitBangError::Pin)?;
+        self.wait();
+        Ok(())
+    }
+
+    /// End an incomplete word. The device ignores it.
+    fn flush(&mut self) -> Result<(), BitBangError<E>> {
+        if self.bit_count != 0 {
+            self.end_word()?;
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, operation: &mut Operation<'_, u8>) -> Result<(), BitBangError<E>> {
+        match operation {
+            Operation::Read(words) => {
+                for word in words.iter_mut() {
+                    self.write_byte(0)?;
+                    *word = 0;
+                }
+                Ok(())
+            }
+            Operation::Write(words) => self.write_bytes(words),
+            Operation::Transfer(read, write) => {
+                self.write_bytes(write)?;
+                read.fill(0);
+                Ok(())
+            }
+            Operation::TransferInPlace(words) => {
+                self.write_bytes(words)?;
+                words.fill(0);
+                Ok(())
+            }
+            Operation::DelayNs(ns) => {
+                self.delay.delay_ns(*ns);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<FSYNC, SCLK, SDATA, D, E> ErrorType for BitBangSpi<FSYNC, SCLK, SDATA, D>
+where
+    FSYNC: OutputPin<Error = E>,
+    E: Debug,
+{
+    type Error = BitBangError<E>;
+}
+
+impl<FSYNC, SCLK, SDATA, D, E> SpiDevice for BitBangSpi<FSYNC, SCLK, SDATA, D>
+where
+    FSYNC: OutputPin<Error = E>,
+    SCLK: OutputPin<Error = E>,
+    SDATA: OutputPin<Error = E>,
+    D: DelayNs,
+    E: Debug,
+{
+    /// The devices do not have a data output. Reading clocks out zeros.
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let result = operations
+            .iter_mut()
+            .try_for_each(|operation| self.run(operation));
+        let flush_result = self.flush();
+        result?;
+        flush_result
+    }
+}
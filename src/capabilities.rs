@@ -0,0 +1,96 @@
+use crate::{Capabilities, OutputWaveform, SquareWavePin, Variant};
+
+impl Variant {
+    /// Get the capabilities and limits of the device variant
+    ///
+    /// The values are taken from the device datasheets.
+    pub fn capabilities(self) -> Capabilities {
+        match self {
+            Variant::Ad9833 => Capabilities {
+                max_mclk_hz: 25_000_000,
+                pin_control: false,
+                triangle_output: true,
+                square_output: true,
+                square_wave_pin: Some(SquareWavePin::Vout),
+                sign_bit_output: false,
+                dac_bits: 10,
+                frequency_bits: 28,
+            },
+            // The AD9834 is specified up to 75 MHz (75 MSPS grade) in its
+            // datasheet, unlike the 25 MHz AD9833.
+            Variant::Ad9834 => Capabilities {
+                max_mclk_hz: 75_000_000,
+                pin_control: true,
+                triangle_output: true,
+                square_output: false,
+                square_wave_pin: Some(SquareWavePin::SignBitOut),
+                sign_bit_output: true,
+                dac_bits: 10,
+                frequency_bits: 28,
+            },
+            Variant::Ad9837 => Capabilities {
+                max_mclk_hz: 16_000_000,
+                pin_control: false,
+                triangle_output: true,
+                square_output: true,
+                square_wave_pin: Some(SquareWavePin::Vout),
+                sign_bit_output: false,
+                dac_bits: 10,
+                frequency_bits: 28,
+            },
+            Variant::Ad9838 => Capabilities {
+                max_mclk_hz: 16_000_000,
+                pin_control: true,
+                triangle_output: true,
+                square_output: false,
+                square_wave_pin: Some(SquareWavePin::SignBitOut),
+                sign_bit_output: true,
+                dac_bits: 10,
+                frequency_bits: 28,
+            },
+            Variant::Ad9832 => Capabilities {
+                max_mclk_hz: 25_000_000,
+                pin_control: true,
+                triangle_output: false,
+                square_output: false,
+                square_wave_pin: None,
+                sign_bit_output: false,
+                dac_bits: 10,
+                frequency_bits: 32,
+            },
+            Variant::Ad9835 => Capabilities {
+                max_mclk_hz: 50_000_000,
+                pin_control: true,
+                triangle_output: false,
+                square_output: false,
+                square_wave_pin: None,
+                sign_bit_output: false,
+                dac_bits: 10,
+                frequency_bits: 32,
+            },
+            Variant::Ad5930 | Variant::Ad5932 => Capabilities {
+                max_mclk_hz: 50_000_000,
+                pin_control: false,
+                triangle_output: true,
+                square_output: false,
+                square_wave_pin: Some(SquareWavePin::MsbOut),
+                sign_bit_output: false,
+                dac_bits: 10,
+                frequency_bits: 24,
+            },
+        }
+    }
+}
+
+impl Capabilities {
+    /// Whether the output waveform can be selected with `set_output_waveform()`
+    pub fn supports_waveform(&self, waveform: OutputWaveform) -> bool {
+        match waveform {
+            OutputWaveform::Sinusoidal => true,
+            OutputWaveform::Triangle => self.triangle_output,
+            OutputWaveform::SquareMsbOfDac | OutputWaveform::SquareMsbOfDacDiv2 => {
+                self.square_output
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Channel manager for many devices
+//!
+//! A [`ChannelManager`] owns a fixed number of devices, for example several
+//! AD9833 on one SPI bus with separate chip-selects, and addresses them by
+//! channel index. It can apply a configuration table to all channels and run
+//! bulk operations on them.
+//!
+//! Bulk operations run on every channel even if some of them fail. The errors
+//! are then reported per channel through [`ChannelErrors`].
+//!
+//! ```
+//! # use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+//! # struct Spi;
+//! # impl ErrorType for Spi { type Error = core::convert::Infallible; }
+//! # impl SpiDevice for Spi {
+//! #     fn transaction(&mut self, _: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let spi = || Spi;
+//! use ad983x::{
+//!     channels::{ChannelConfig, ChannelManager},
+//!     Ad983x, OutputWaveform, PoweredDown,
+//! };
+//!
+//! let mut channels = ChannelManager::new([
+//!     Ad983x::new_ad9833(spi()),
+//!     Ad983x::new_ad9833(spi()),
+//!     Ad983x::new_ad9833(spi()),
+//! ]);
+//! let sine = ChannelConfig {
+//!     frequency: 4724,
+//!     ..ChannelConfig::default()
+//! };
+//! let triangle = ChannelConfig {
+//!     waveform: OutputWaveform::Triangle,
+//!     ..sine
+//! };
+//! channels.apply(&[sine, triangle, sine]).unwrap();
+//! channels.enable_all().unwrap();
+//! if let Err(errors) = channels.set_powered_down_all(PoweredDown::Dac) {
+//!     for (channel, error) in errors.iter() {
+//!         eprintln!("channel {}: {:?}", channel, error);
+//!     }
+//! }
+//! ```
+
+use crate::{Ad983x, Dds, Error, FrequencyRegister, OutputWaveform, PhaseRegister, PoweredDown};
+
+/// Configuration of a channel
+///
+/// The frequency and phase are written to the F0 and P0 registers, which are
+/// then selected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelConfig {
+    /// Frequency register value
+    pub frequency: u32,
+    /// Phase register value (12-bit)
+    pub phase: u16,
+    /// Output waveform
+    pub waveform: OutputWaveform,
+    /// Powered-down device parts
+    pub powered_down: PoweredDown,
+    /// Whether the channel is enabled (reset disabled) afterwards
+    pub enabled: bool,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        ChannelConfig {
+            frequency: 0,
+            phase: 0,
+            waveform: OutputWaveform::Sinusoidal,
+            powered_down: PoweredDown::Nothing,
+            enabled: false,
+        }
+    }
+}
+
+/// Errors of a bulk operation per channel
+#[derive(Debug)]
+pub struct ChannelErrors<E, const N: usize> {
+    errors: [Option<E>; N],
+}
+
+impl<E, const N: usize> ChannelErrors<E, N> {
+    /// Get the error of a channel, if it failed
+    pub fn get(&self, channel: usize) -> Option<&E> {
+        self.errors.get(channel).and_then(Option::as_ref)
+    }
+
+    /// Iterate over the failed channels and their errors
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &E)> + '_ {
+        self.errors
+            .iter()
+            .enumerate()
+            .filter_map(|(channel, error)| error.as_ref().map(|error| (channel, error)))
+    }
+
+    /// Get the number of failed channels
+    pub fn count(&self) -> usize {
+        self.errors.iter().filter(|error| error.is_some()).count()
+    }
+
+    /// Get the errors indexed by channel
+    pub fn into_inner(self) -> [Option<E>; N] {
+        self.errors
+    }
+}
+
+/// Manager of a fixed number of devices addressed by channel index
+#[derive(Debug)]
+pub struct ChannelManager<DEV, IC, const N: usize> {
+    channels: [Ad983x<DEV, IC>; N],
+}
+
+impl<DEV, IC, const N: usize> ChannelManager<DEV, IC, N> {
+    /// Create a new manager. The channel index is the index in the array.
+    pub fn new(channels: [Ad983x<DEV, IC>; N]) -> Self {
+        ChannelManager { channels }
+    }
+
+    /// Get a channel
+    ///
+    /// Returns `None` if the index is out of range.
+    pub fn channel(&mut self, channel: usize) -> Option<&mut Ad983x<DEV, IC>> {
+        self.channels.get_mut(channel)
+    }
+
+    /// Get all channels
+    pub fn channels(&mut self) -> &mut [Ad983x<DEV, IC>; N] {
+        &mut self.channels
+    }
+
+    /// Destroy the manager and return the devices.
+    pub fn destroy(self) -> [Ad983x<DEV, IC>; N] {
+        self.channels
+    }
+}
+
+impl<DEV, IC, E, const N: usize> ChannelManager<DEV, IC, N>
+where
+    Ad983x<DEV, IC>: Dds<Error = Error<E>>,
+{
+    /// Apply a configuration to a channel.
+    ///
+    /// Returns `Error::InvalidChannel` if there is no channel with the given index.
+    pub fn configure(&mut self, channel: usize, config: &ChannelConfig) -> Result<(), Error<E>> {
+        let dds = self
+            .channels
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel { index: channel })?;
+        trace_op!("configure channel {}", channel);
+        apply_config(dds, config)
+    }
+
+    /// Apply a configuration table with one entry per channel.
+    pub fn apply(&mut self, table: &[ChannelConfig; N]) -> Result<(), ChannelErrors<Error<E>, N>> {
+        trace_op!("apply table to {} channels", N);
+        self.for_all(|channel, dds| apply_config(dds, &table[channel]))
+    }
+
+    /// Enable all channels (disable reset)
+    pub fn enable_all(&mut self) -> Result<(), ChannelErrors<Error<E>, N>> {
+        trace_op!("enable {} channels", N);
+        self.for_all(|_, dds| dds.enable())
+    }
+
+    /// Disable all channels (enable reset)
+    pub fn disable_all(&mut self) -> Result<(), ChannelErrors<Error<E>, N>> {
+        trace_op!("disable {} channels", N);
+        self.for_all(|_, dds| dds.disable())
+    }
+
+    /// Set the powered-down state of all channels
+    pub fn set_powered_down_all(
+        &mut self,
+        config: PoweredDown,
+    ) -> Result<(), ChannelErrors<Error<E>, N>> {
+        trace_op!("set_powered_down {:?} on {} channels", config, N);
+        self.for_all(|_, dds| dds.set_powered_down(config))
+    }
+
+    /// Set a frequency register of all channels
+    pub fn set_frequency_all(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<(), ChannelErrors<Error<E>, N>> {
+        trace_op!(
+            "set_frequency {:?} = {:#x} on {} channels",
+            register,
+            value,
+            N
+        );
+        self.for_all(|_, dds| dds.set_frequency(register, value))
+    }
+
+    /// Run an operation on every channel and collect the errors.
+    fn for_all<F>(&mut self, mut operation: F) -> Result<(), ChannelErrors<Error<E>, N>>
+    where
+        F: FnMut(usize, &mut Ad983x<DEV, IC>) -> Result<(), Error<E>>,
+    {
+        let mut errors = ChannelErrors {
+            errors: [(); N].map(|_| None),
+        };
+        for (channel, dds) in self.channels.iter_mut().enumerate() {
+            errors.errors[channel] = operation(channel, dds).err();
+        }
+        if errors.count() == 0 {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn apply_config<D, E>(dds: &mut D, config: &ChannelConfig) -> Result<(), E>
+where
+    D: Dds<Error = E>,
+{
+    dds.set_frequency(FrequencyRegister::F0, config.frequency)?;
+    dds.select_frequency(FrequencyRegister::F0)?;
+    dds.set_phase(PhaseRegister::P0, config.phase)?;
+    dds.select_phase(PhaseRegister::P0)?;
+    dds.set_output_waveform(config.waveform)?;
+    dds.set_powered_down(config.powered_down)?;
+    if config.enabled {
+        dds.enable()
+    } else {
+        dds.disable()
+    }
+}
@@ -1,6 +1,7 @@
-use embedded_hal::spi::blocking::{SpiBus, SpiDevice};
-
-use crate::{Ad983x, BitFlags, Config, Error, FrequencyRegister, PhaseRegister, PoweredDown};
+use crate::{
+    encoder, private, Ad983x, BitFlags, Capabilities, Config, ControlWord, Error,
+    FrequencyRegister, OutputWaveform, PhaseRegister, PoweredDown, RetryPolicy, Variant, WriteWord,
+};
 use core::marker::PhantomData;
 
 impl Config {
@@ -14,6 +15,86 @@ impl Config {
             bits: self.bits & !mask,
         }
     }
+
+    pub(crate) fn with_frequency_select(self, register: FrequencyRegister) -> Self {
+        match register {
+            FrequencyRegister::F0 => self.with_low(BitFlags::FSELECT),
+            FrequencyRegister::F1 => self.with_high(BitFlags::FSELECT),
+        }
+    }
+
+    pub(crate) fn with_phase_select(self, register: PhaseRegister) -> Self {
+        match register {
+            PhaseRegister::P0 => self.with_low(BitFlags::PSELECT),
+            PhaseRegister::P1 => self.with_high(BitFlags::PSELECT),
+        }
+    }
+
+    pub(crate) fn with_powered_down(self, config: PoweredDown) -> Self {
+        match config {
+            PoweredDown::Nothing => self
+                .with_low(BitFlags::SLEEP_MCLK)
+                .with_low(BitFlags::SLEEP_DAC),
+            PoweredDown::Dac => self
+                .with_low(BitFlags::SLEEP_MCLK)
+                .with_high(BitFlags::SLEEP_DAC),
+            PoweredDown::InternalClock => self
+                .with_high(BitFlags::SLEEP_MCLK)
+                .with_low(BitFlags::SLEEP_DAC),
+            PoweredDown::DacAndInternalClock => self
+                .with_high(BitFlags::SLEEP_MCLK)
+                .with_high(BitFlags::SLEEP_DAC),
+        }
+    }
+}
+
+pub(crate) fn check_value_fits<T, E>(value: T, bit_count: u8) -> Result<(), Error<E>>
+where
+    T: From<u8> + PartialOrd + core::ops::Shl<Output = T>,
+{
+    if value >= (T::from(1) << T::from(bit_count)) {
+        Err(Error::ValueTooWide { bits: bit_count })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a control register value written as a whole against the device features
+pub(crate) fn check_control_word<E>(variant: Variant, word: ControlWord) -> Result<(), Error<E>> {
+    let pin_bits = BitFlags::PIN_SW | BitFlags::SIGN_PIB;
+    if !variant.capabilities().pin_control && word.bits & pin_bits != 0 {
+        return Err(Error::Unsupported);
+    }
+    if word.bits & BitFlags::OPBITEN != 0 && word.bits & BitFlags::MODE != 0 {
+        // The triangle output cannot be used together with the digital output
+        return Err(Error::UnsupportedWaveform(OutputWaveform::Triangle));
+    }
+    Ok(())
+}
+
+/// Check an MCLK frequency against the device limits
+pub(crate) fn check_mclk_frequency<E>(variant: Variant, mclk_hz: u32) -> Result<(), Error<E>> {
+    let max_hz = variant.capabilities().max_mclk_hz;
+    if mclk_hz == 0 || mclk_hz > max_hz {
+        Err(Error::InvalidMclkFrequency { max_hz })
+    } else {
+        Ok(())
+    }
+}
+
+/// Value of the frequency register for a frequency in Hz
+pub(crate) fn frequency_register_value<E>(
+    variant: Variant,
+    mclk_hz: Option<u32>,
+    frequency_hz: f64,
+) -> Result<u32, Error<E>> {
+    let mclk_hz = f64::from(mclk_hz.ok_or(Error::MclkFrequencyNotSet)?);
+    let max_hz = mclk_hz / 2.0;
+    if !(0.0..=max_hz).contains(&frequency_hz) {
+        return Err(Error::FrequencyOutOfRange { max_hz });
+    }
+    let steps = (1_u64 << variant.capabilities().frequency_bits) as f64;
+    Ok((frequency_hz * steps / mclk_hz + 0.5) as u32)
 }
 
 impl BitFlags {
@@ -35,16 +116,62 @@ impl BitFlags {
 }
 
 impl<DEV, IC> Ad983x<DEV, IC> {
-    pub(crate) fn create(spi: DEV) -> Self {
+    pub(crate) fn create(spi: DEV, variant: Variant) -> Self {
         Ad983x {
             spi,
             control: Config {
                 bits: BitFlags::RESET,
             },
+            variant,
+            mclk_hz: None,
+            desynchronized: false,
+            retry_policy: RetryPolicy::default(),
+            retries: 0,
             _ic: PhantomData,
         }
     }
 
+    /// Get the device variant
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Get the device capabilities and limits
+    pub fn capabilities(&self) -> Capabilities {
+        self.variant.capabilities()
+    }
+
+    /// Get the configured master clock (MCLK) frequency in Hz, if any
+    pub fn mclk_frequency(&self) -> Option<u32> {
+        self.mclk_hz
+    }
+
+    /// Whether the device state may differ from the driver state.
+    ///
+    /// This is the case after an SPI communication error until the control
+    /// register has been written again. This happens on the next operation
+    /// or through `resync()`.
+    pub fn is_desynchronized(&self) -> bool {
+        self.desynchronized
+    }
+
+    /// Set the retry policy for SPI communication errors
+    ///
+    /// By default failed words are not sent again.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Get the retry policy for SPI communication errors
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Get the number of retries that happened since the creation of the driver
+    pub fn retry_count(&self) -> u32 {
+        self.retries
+    }
+
     /// Destroy driver instance, return SPI bus instance and CS output pin.
     pub fn destroy(self) -> DEV {
         self.spi
@@ -53,8 +180,54 @@ impl<DEV, IC> Ad983x<DEV, IC> {
 
 impl<DEV, IC, E> Ad983x<DEV, IC>
 where
-    DEV: SpiDevice<Error = E>,
-    DEV::Bus: SpiBus,
+    DEV: WriteWord<Error = E>,
+    IC: private::Sealed,
+{
+    /// Set the master clock (MCLK) frequency in Hz
+    ///
+    /// This is only used to convert frequencies in Hz into frequency register values.
+    /// Returns `Error::InvalidMclkFrequency` if the frequency exceeds the maximum
+    /// MCLK frequency of the device.
+    pub fn set_mclk_frequency(&mut self, mclk_hz: u32) -> Result<(), Error<E>> {
+        check_mclk_frequency(self.variant, mclk_hz)?;
+        self.mclk_hz = Some(mclk_hz);
+        Ok(())
+    }
+
+    pub(crate) fn frequency_register_value(&self, frequency_hz: f64) -> Result<u32, Error<E>> {
+        frequency_register_value(self.variant, self.mclk_hz, frequency_hz)
+    }
+
+    /// Run a write operation, running it again on SPI communication errors
+    /// according to the retry policy.
+    ///
+    /// The operation must bring the device back in sync itself if needed.
+    pub(crate) fn with_retries<F>(&mut self, mut write: F) -> Result<(), Error<E>>
+    where
+        F: FnMut(&mut Self) -> Result<(), Error<E>>,
+    {
+        let mut attempts = 1;
+        loop {
+            match write(self) {
+                Ok(()) => return Ok(()),
+                Err(_) if attempts < self.retry_policy.max_attempts => {
+                    attempts += 1;
+                    self.retries = self.retries.saturating_add(1);
+                    trace_op!("retry attempt {}", attempts);
+                    if let Some(delay) = self.retry_policy.delay {
+                        delay();
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<DEV, IC, E> Ad983x<DEV, IC>
+where
+    DEV: WriteWord<Error = E>,
+    IC: private::Ad983xFamily,
 {
     /// Resets the internal registers and leaves the device disabled.
     ///
@@ -70,6 +243,7 @@ where
     /// Note that this is ignored in AD9834/AD9838 devices if hardware pin
     /// control source is selected.
     pub fn disable(&mut self) -> Result<(), Error<E>> {
+        trace_op!("disable");
         let control = self.control.with_high(BitFlags::RESET);
         self.write_control(control)
     }
@@ -79,80 +253,76 @@ where
     /// Note that this is ignored in AD9834/AD9838 devices if hardware pin
     /// control source is selected.
     pub fn enable(&mut self) -> Result<(), Error<E>> {
+        trace_op!("enable");
         let control = self.control.with_low(BitFlags::RESET);
         self.write_control(control)
     }
 
-    fn check_value_fits<T>(value: T, bit_count: T) -> Result<(), Error<E>>
-    where
-        T: From<u8> + PartialOrd + core::ops::Shl<Output = T>,
-    {
-        if value >= (T::from(1) << bit_count) {
-            Err(Error::InvalidArgument)
-        } else {
-            Ok(())
-        }
-    }
-
     /// Set the frequency as a 28-bit word
     ///
     /// This will change the mode to 28-bit if it is not used.
-    /// Returns `Error::InvalidArgument` if providing a value that does not fit in 28 bits.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 28 bits.
     pub fn set_frequency(
         &mut self,
         register: FrequencyRegister,
         value: u32,
     ) -> Result<(), Error<E>> {
-        Self::check_value_fits(value, 28)?;
+        check_value_fits(value, 28)?;
+        trace_op!("set_frequency {:?} = {:#x}", register, value);
         let control = self.control.with_high(BitFlags::B28);
         self.write_control_if_different(control)?;
-        let lsb = value & ((1 << 14) - 1);
-        let msb = value >> 14;
-        let reg = Self::get_freq_register_bits(register);
-        self.write(reg | lsb as u16)?;
-        self.write(reg | msb as u16)
+        self.write_sequence(&encoder::frequency_words(register, value))
     }
 
-    fn get_freq_register_bits(register: FrequencyRegister) -> u16 {
-        match register {
-            FrequencyRegister::F0 => BitFlags::D14,
-            FrequencyRegister::F1 => BitFlags::D15,
-        }
+    /// Set the frequency in Hz
+    ///
+    /// The value written into the frequency register is calculated from the
+    /// configured MCLK frequency. See [`set_mclk_frequency()`](#method.set_mclk_frequency).
+    /// Returns `Error::MclkFrequencyNotSet` if no MCLK frequency has been configured or
+    /// `Error::FrequencyOutOfRange` if the frequency is negative or exceeds half
+    /// the MCLK frequency.
+    pub fn set_frequency_hz(
+        &mut self,
+        register: FrequencyRegister,
+        frequency_hz: f64,
+    ) -> Result<(), Error<E>> {
+        let value = self.frequency_register_value(frequency_hz)?;
+        self.set_frequency(register, value)
     }
 
     /// Set the frequency 14-bit MSBs
     ///
     /// This will deactivate the 28-bit mode if it is not already the case.
-    /// Returns `Error::InvalidArgument` if providing a value that does not fit in 14 bits.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 14 bits.
     pub fn set_frequency_msb(
         &mut self,
         register: FrequencyRegister,
         value: u16,
     ) -> Result<(), Error<E>> {
-        Self::check_value_fits(value, 14)?;
+        check_value_fits(value, 14)?;
+        trace_op!("set_frequency_msb {:?} = {:#x}", register, value);
         let control = self
             .control
             .with_low(BitFlags::B28)
             .with_high(BitFlags::HLB);
         self.write_control_if_different(control)?;
-        let reg = Self::get_freq_register_bits(register);
-        self.write(reg | value as u16)
+        self.write(encoder::frequency_half_word(register, value))
     }
 
     /// Set the frequency 14-bit LSBs
     ///
     /// This will deactivate the 28-bit mode if it is not already the case.
-    /// Returns `Error::InvalidArgument` if providing a value that does not fit in 14 bits.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 14 bits.
     pub fn set_frequency_lsb(
         &mut self,
         register: FrequencyRegister,
         value: u16,
     ) -> Result<(), Error<E>> {
-        Self::check_value_fits(value, 14)?;
+        check_value_fits(value, 14)?;
+        trace_op!("set_frequency_lsb {:?} = {:#x}", register, value);
         let control = self.control.with_low(BitFlags::B28).with_low(BitFlags::HLB);
         self.write_control_if_different(control)?;
-        let reg = Self::get_freq_register_bits(register);
-        self.write(reg | value as u16)
+        self.write(encoder::frequency_half_word(register, value))
     }
 
     /// Select the frequency register that is used
@@ -160,24 +330,18 @@ where
     /// Note: this can be overriden through the FSELECT pin in AD9834/AD9838
     /// devices if hardware pin control source is selected.
     pub fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Error<E>> {
-        let control = match register {
-            FrequencyRegister::F0 => self.control.with_low(BitFlags::FSELECT),
-            FrequencyRegister::F1 => self.control.with_high(BitFlags::FSELECT),
-        };
+        trace_op!("select_frequency {:?}", register);
+        let control = self.control.with_frequency_select(register);
         self.write_control(control)
     }
 
     /// Set a phase register (12-bit value)
     ///
-    /// Returns `Error::InvalidArgument` if providing a value that does not fit in 12 bits.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 12 bits.
     pub fn set_phase(&mut self, register: PhaseRegister, value: u16) -> Result<(), Error<E>> {
-        Self::check_value_fits(value, 12)?;
-        let value = value | BitFlags::D14 | BitFlags::D15;
-        let value = match register {
-            PhaseRegister::P0 => value,
-            PhaseRegister::P1 => value | BitFlags::D13,
-        };
-        self.write(value)
+        check_value_fits(value, 12)?;
+        trace_op!("set_phase {:?} = {}", register, value);
+        self.write(encoder::phase_word(register, value))
     }
 
     /// Select the phase register that is used.
@@ -185,10 +349,8 @@ where
     /// Note: this can be overriden through the PSELECT pin in AD9834/AD9838
     /// devices if hardware pin control source is selected.
     pub fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Error<E>> {
-        let control = match register {
-            PhaseRegister::P0 => self.control.with_low(BitFlags::PSELECT),
-            PhaseRegister::P1 => self.control.with_high(BitFlags::PSELECT),
-        };
+        trace_op!("select_phase {:?}", register);
+        let control = self.control.with_phase_select(register);
         self.write_control(control)
     }
 
@@ -197,29 +359,44 @@ where
     /// Note: This can be overriden through the SLEEP pin
     /// in AD9834/AD9838 devices if hardware pin control source is selected.
     pub fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Error<E>> {
-        let control = match config {
-            PoweredDown::Nothing => self
-                .control
-                .with_low(BitFlags::SLEEP_MCLK)
-                .with_low(BitFlags::SLEEP_DAC),
-            PoweredDown::Dac => self
-                .control
-                .with_low(BitFlags::SLEEP_MCLK)
-                .with_high(BitFlags::SLEEP_DAC),
-            PoweredDown::InternalClock => self
-                .control
-                .with_high(BitFlags::SLEEP_MCLK)
-                .with_low(BitFlags::SLEEP_DAC),
-            PoweredDown::DacAndInternalClock => self
-                .control
-                .with_high(BitFlags::SLEEP_MCLK)
-                .with_high(BitFlags::SLEEP_DAC),
-        };
+        trace_op!("set_powered_down {:?}", config);
+        let control = self.control.with_powered_down(config);
+        self.write_control(control)
+    }
+
+    /// Get the control register value as known by the driver
+    pub fn control_word(&self) -> ControlWord {
+        ControlWord {
+            bits: self.control.bits,
+        }
+    }
+
+    /// Write the control register value.
+    ///
+    /// This sets several control fields in one write. The driver state is
+    /// updated accordingly.
+    /// Returns `Error::Unsupported` if setting PIN/SW or SIGN/PIB on
+    /// AD9833/AD9837 devices.
+    /// Returns `Error::UnsupportedWaveform` with `OutputWaveform::Triangle` if
+    /// setting MODE together with OPBITEN, as the triangle output cannot be
+    /// used together with the digital output.
+    pub fn write_control_word(&mut self, word: ControlWord) -> Result<(), Error<E>> {
+        check_control_word(self.variant, word)?;
+        trace_op!("write_control_word {:#06x}", word.bits);
+        self.write_control(Config { bits: word.bits })
+    }
+
+    /// Write the control register with the current driver state.
+    ///
+    /// This brings the device back in sync after an SPI communication error.
+    pub fn resync(&mut self) -> Result<(), Error<E>> {
+        trace_op!("resync");
+        let control = self.control;
         self.write_control(control)
     }
 
     pub(crate) fn write_control_if_different(&mut self, control: Config) -> Result<(), Error<E>> {
-        if control != self.control {
+        if control != self.control || self.desynchronized {
             self.write_control(control)
         } else {
             Ok(())
@@ -227,15 +404,69 @@ where
     }
 
     pub(crate) fn write_control(&mut self, control: Config) -> Result<(), Error<E>> {
-        let payload = control.bits & 0b0011_1111_1111_1111;
-        self.write(payload)?;
+        let payload = encoder::control_payload(control.bits);
+        trace_op!("control {:#06x} -> {:#06x}", self.control.bits, payload);
+        self.with_retries(|dev| dev.write_word(payload))?;
         self.control = control;
+        self.desynchronized = false;
         Ok(())
     }
 
+    /// Write a word to a register other than the control register.
+    ///
+    /// The control register is written first if its state is unknown.
     pub(crate) fn write(&mut self, payload: u16) -> Result<(), Error<E>> {
-        self.spi
-            .write(&[(payload >> 8) as u8, payload as u8])
-            .map_err(Error::Spi)
+        self.write_sequence(&[payload])
+    }
+
+    /// Write words to registers other than the control register.
+    ///
+    /// The control register is written first if its state is unknown, also
+    /// before sending the whole sequence again on retries.
+    pub(crate) fn write_sequence(&mut self, words: &[u16]) -> Result<(), Error<E>> {
+        self.with_retries(|dev| {
+            if dev.desynchronized {
+                // Written without retries of its own, the whole sequence
+                // is already retried.
+                trace_op!("resync");
+                dev.write_word(encoder::control_payload(dev.control.bits))?;
+                dev.desynchronized = false;
+            }
+            words.iter().try_for_each(|word| dev.write_word(*word))
+        })
+    }
+
+    fn write_word(&mut self, payload: u16) -> Result<(), Error<E>> {
+        trace_word!(
+            "word {:#06x}: {} = {:#x}",
+            payload,
+            register_name(payload),
+            register_data(payload)
+        );
+        self.spi.write_word(payload).map_err(|e| {
+            self.desynchronized = true;
+            Error::Spi(e)
+        })
+    }
+}
+
+/// Name of the register written to by a word
+#[cfg(any(feature = "log", feature = "defmt"))]
+pub(crate) fn register_name(payload: u16) -> &'static str {
+    match payload & (BitFlags::D15 | BitFlags::D14) {
+        0 => "CONTROL",
+        BitFlags::D14 => "FREQ0",
+        BitFlags::D15 => "FREQ1",
+        _ if payload & BitFlags::D13 != 0 => "PHASE1",
+        _ => "PHASE0",
+    }
+}
+
+/// Data bits of a word
+#[cfg(any(feature = "log", feature = "defmt"))]
+pub(crate) fn register_data(payload: u16) -> u16 {
+    match payload & (BitFlags::D15 | BitFlags::D14) {
+        0 | BitFlags::D14 | BitFlags::D15 => payload & 0x3FFF,
+        _ => payload & 0x0FFF,
     }
 }
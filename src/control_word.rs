@@ -0,0 +1,86 @@
+use crate::{BitFlags, ControlWord, FrequencyRegister, PhaseRegister};
+
+impl ControlWord {
+    /// Create a new control word with all bits cleared
+    pub fn new() -> Self {
+        ControlWord::default()
+    }
+
+    /// Get the value written to the control register (D15 and D14 are cleared)
+    pub fn bits(self) -> u16 {
+        self.bits
+    }
+
+    fn with(self, mask: u16, enabled: bool) -> Self {
+        let bits = if enabled {
+            self.bits | mask
+        } else {
+            self.bits & !mask
+        };
+        ControlWord { bits }
+    }
+
+    /// Write the frequency registers as a 28-bit word in two consecutive writes (B28)
+    pub fn b28(self, enabled: bool) -> Self {
+        self.with(BitFlags::B28, enabled)
+    }
+
+    /// Write the frequency register MSBs instead of the LSBs if B28 is not set (HLB)
+    pub fn hlb(self, enabled: bool) -> Self {
+        self.with(BitFlags::HLB, enabled)
+    }
+
+    /// Select the frequency register used in the phase accumulator (FSELECT)
+    pub fn fselect(self, register: FrequencyRegister) -> Self {
+        self.with(BitFlags::FSELECT, register == FrequencyRegister::F1)
+    }
+
+    /// Select the phase register added to the phase accumulator output (PSELECT)
+    pub fn pselect(self, register: PhaseRegister) -> Self {
+        self.with(BitFlags::PSELECT, register == PhaseRegister::P1)
+    }
+
+    /// Control the functions through the hardware pins (PIN/SW)
+    ///
+    /// This is only available on AD9834/AD9838 devices.
+    pub fn pin_sw(self, enabled: bool) -> Self {
+        self.with(BitFlags::PIN_SW, enabled)
+    }
+
+    /// Reset the internal registers (RESET)
+    pub fn reset(self, enabled: bool) -> Self {
+        self.with(BitFlags::RESET, enabled)
+    }
+
+    /// Disable the internal MCLK (SLEEP1)
+    pub fn sleep1(self, enabled: bool) -> Self {
+        self.with(BitFlags::SLEEP_MCLK, enabled)
+    }
+
+    /// Power down the DAC (SLEEP12)
+    pub fn sleep12(self, enabled: bool) -> Self {
+        self.with(BitFlags::SLEEP_DAC, enabled)
+    }
+
+    /// Enable the digital output (OPBITEN)
+    pub fn opbiten(self, enabled: bool) -> Self {
+        self.with(BitFlags::OPBITEN, enabled)
+    }
+
+    /// Output the comparator on the SIGN BIT OUT pin (SIGN/PIB)
+    ///
+    /// This is only available on AD9834/AD9838 devices.
+    pub fn sign_pib(self, enabled: bool) -> Self {
+        self.with(BitFlags::SIGN_PIB, enabled)
+    }
+
+    /// Output the DAC data MSB instead of MSB/2 (DIV2)
+    pub fn div2(self, enabled: bool) -> Self {
+        self.with(BitFlags::DIV2, enabled)
+    }
+
+    /// Bypass the sine ROM to output a triangle waveform (MODE)
+    pub fn mode(self, enabled: bool) -> Self {
+        self.with(BitFlags::MODE, enabled)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    marker, Ad983x, Error, FrequencyRegister, OutputWaveform, PhaseRegister, PoweredDown,
+    SquareWave, SquareWavePin, WriteWord,
+};
+
+/// Operations common to all supported devices.
+///
+/// This trait is object-safe so application code can be written once for
+/// any device, for example by taking a `&mut dyn Dds<Error = Error<E>>`.
+pub trait Dds {
+    /// Error type
+    type Error;
+
+    /// Resets the internal registers and leaves the device disabled.
+    fn reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Disable the device (enable reset)
+    fn disable(&mut self) -> Result<(), Self::Error>;
+
+    /// Enable the device (disable reset)
+    fn enable(&mut self) -> Result<(), Self::Error>;
+
+    /// Set the frequency register value
+    ///
+    /// This is a 28-bit word on AD9833/AD9834/AD9837/AD9838 devices and
+    /// a 32-bit word on AD9832/AD9835 devices.
+    fn set_frequency(&mut self, register: FrequencyRegister, value: u32)
+        -> Result<(), Self::Error>;
+
+    /// Select the frequency register that is used
+    fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Self::Error>;
+
+    /// Set a phase register (12-bit value)
+    fn set_phase(&mut self, register: PhaseRegister, value: u16) -> Result<(), Self::Error>;
+
+    /// Select the phase register that is used
+    fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Self::Error>;
+
+    /// Set device parts powered-down state
+    fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Self::Error>;
+
+    /// Set the output waveform
+    ///
+    /// Not all waveforms are available on all devices.
+    fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Self::Error>;
+
+    /// Output a square wave
+    ///
+    /// Returns the pin where the square wave is available.
+    /// Returns `Error::Unsupported` if the device cannot output a square wave.
+    fn set_square_wave_output(
+        &mut self,
+        square_wave: SquareWave,
+    ) -> Result<SquareWavePin, Self::Error>;
+
+    /// Output pin where the device can provide a square wave, if any
+    fn square_wave_pin(&self) -> Option<SquareWavePin>;
+}
+
+macro_rules! impl_dds {
+    ($ic:ty) => {
+        impl<DEV, E> Dds for Ad983x<DEV, $ic>
+        where
+            DEV: WriteWord<Error = E>,
+        {
+            type Error = Error<E>;
+
+            fn reset(&mut self) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::reset(self)
+            }
+
+            fn disable(&mut self) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::disable(self)
+            }
+
+            fn enable(&mut self) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::enable(self)
+            }
+
+            fn set_frequency(
+                &mut self,
+                register: FrequencyRegister,
+                value: u32,
+            ) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::set_frequency(self, register, value)
+            }
+
+            fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::select_frequency(self, register)
+            }
+
+            fn set_phase(
+                &mut self,
+                register: PhaseRegister,
+                value: u16,
+            ) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::set_phase(self, register, value)
+            }
+
+            fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::select_phase(self, register)
+            }
+
+            fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::set_powered_down(self, config)
+            }
+
+            fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Self::Error> {
+                Ad983x::<DEV, $ic>::set_output_waveform(self, waveform)
+            }
+
+            fn set_square_wave_output(
+                &mut self,
+                square_wave: SquareWave,
+            ) -> Result<SquareWavePin, Self::Error> {
+                Ad983x::<DEV, $ic>::set_square_wave_output(self, square_wave)
+            }
+
+            fn square_wave_pin(&self) -> Option<SquareWavePin> {
+                self.capabilities().square_wave_pin
+            }
+        }
+    };
+}
+
+impl_dds!(marker::Ad9833Ad9837);
+impl_dds!(marker::Ad9834Ad9838);
+
+impl<DEV, E> Dds for Ad983x<DEV, marker::Ad9832Ad9835>
+where
+    DEV: WriteWord<Error = E>,
+{
+    type Error = Error<E>;
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::reset(self)
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::disable(self)
+    }
+
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::enable(self)
+    }
+
+    fn set_frequency(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::set_frequency(self, register, value)
+    }
+
+    fn select_frequency(&mut self, register: FrequencyRegister) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::select_frequency(self, register)
+    }
+
+    fn set_phase(&mut self, register: PhaseRegister, value: u16) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::set_phase(self, register, value)
+    }
+
+    fn select_phase(&mut self, register: PhaseRegister) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::select_phase(self, register)
+    }
+
+    fn set_powered_down(&mut self, config: PoweredDown) -> Result<(), Self::Error> {
+        Ad983x::<DEV, marker::Ad9832Ad9835>::set_powered_down(self, config)
+    }
+
+    /// Only `OutputWaveform::Sinusoidal` is available on AD9832/AD9835 devices.
+    /// Selecting it does not need any communication with the device.
+    fn set_output_waveform(&mut self, waveform: OutputWaveform) -> Result<(), Self::Error> {
+        match waveform {
+            OutputWaveform::Sinusoidal => Ok(()),
+            _ => Err(Error::UnsupportedWaveform(waveform)),
+        }
+    }
+
+    fn set_square_wave_output(&mut self, _: SquareWave) -> Result<SquareWavePin, Self::Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn square_wave_pin(&self) -> Option<SquareWavePin> {
+        None
+    }
+}
@@ -0,0 +1,749 @@
+//! Decoder of captured SPI traffic into commands
+//!
+//! The [`Decoder`] turns the 16-bit words sent to a device into typed
+//! [`Command`]s. All device variants are supported:
+//! - AD9833/AD9834/AD9837/AD9838: the decoder follows the B28/HLB sequencing
+//!   like the device does, so frequency writes are reported as the 14 LSBs
+//!   or MSBs of the register.
+//! - AD9832/AD9835: 16-bit register writes are reported together with the
+//!   byte held in the defer register.
+//! - AD5930/AD5932: the sweep registers are reported with their fields.
+//!
+//! Hex dumps and sigrok/PulseView CSV exports can be parsed into words
+//! with [`parse_hex()`] and [`parse_csv()`].
+//!
+//! This module is available with the `decoder` feature.
+//!
+//! ```
+//! use ad983x::decoder::{parse_hex, Decoder};
+//! use ad983x::Variant;
+//!
+//! let words = parse_hex("21 00 52 74 40 00 c4 00 08 00").unwrap();
+//! let mut decoder = Decoder::new(Variant::Ad9833);
+//! for word in words {
+//!     println!("{}", decoder.decode(word));
+//! }
+//! // control: B28 on, HLB off, FSELECT=F0, PSELECT=P0, RESET on, SLEEP1 off, SLEEP12 off, OPBITEN off, DIV2 off, MODE off
+//! // FREQ0 LSB = 0x1274
+//! // FREQ0 MSB = 0x0000
+//! // PHASE0 = 1024
+//! // control: B28 off, HLB off, FSELECT=F1, PSELECT=P0, RESET off, SLEEP1 off, SLEEP12 off, OPBITEN off, DIV2 off, MODE off
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    ad5930_ad5932::BitFlags as SweepBitFlags, ad9832_ad9835::BitFlags as CommandBitFlags, BitFlags,
+    ExtendedPhaseRegister, FrequencyRegister, IncrementInterval, IncrementIntervalBase,
+    IncrementIntervalMultiplier, PhaseRegister, Variant,
+};
+
+/// Reserved control bits on AD9833/AD9834/AD9837/AD9838 devices
+const RESERVED: u16 = 1 << 2 | 1;
+/// Command selecting the phase register only (AD9832/AD9835)
+const CMD_PSELECT: u16 = 0b0100 << 12;
+/// Command selecting the frequency register only (AD9832/AD9835)
+const CMD_FSELECT: u16 = 0b0101 << 12;
+/// Clear the SYNC and SELSRC settings (AD9832/AD9835)
+const CLR: u16 = 1 << 11;
+
+/// Decoded control register write
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Control {
+    /// Frequency registers are written with two consecutive 14-bit writes
+    pub b28: bool,
+    /// 14 MSBs are written when `b28` is off
+    pub hlb: bool,
+    /// Selected frequency register
+    pub fselect: FrequencyRegister,
+    /// Selected phase register
+    pub pselect: PhaseRegister,
+    /// Hardware pin control (AD9834/AD9838 only)
+    pub pin_sw: Option<bool>,
+    /// Internal registers held in reset
+    pub reset: bool,
+    /// Internal clock disabled (SLEEP1)
+    pub sleep_mclk: bool,
+    /// DAC powered down (SLEEP12)
+    pub sleep_dac: bool,
+    /// Square/sign bit output enabled
+    pub opbiten: bool,
+    /// Comparator output on SIGN BIT OUT (AD9834/AD9838 only)
+    pub sign_pib: Option<bool>,
+    /// MSB of DAC data is output instead of MSB/2
+    pub div2: bool,
+    /// Triangle output
+    pub mode: bool,
+    /// Reserved bits that were set
+    pub reserved: u16,
+}
+
+/// Decoded control register write of AD5930/AD5932 devices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepControl {
+    /// Frequency registers are written with two consecutive 12-bit writes
+    pub b24: bool,
+    /// DAC enabled
+    pub dac_enable: bool,
+    /// Sine output (triangle output otherwise)
+    pub sine_tri: bool,
+    /// Square wave output on MSBOUT enabled
+    pub msbouten: bool,
+    /// Continuous output after the sweep (AD5930 only)
+    pub cw_burst: Option<bool>,
+    /// Frequency increments triggered externally through the CTRL pin
+    pub int_ext_incr: bool,
+    /// SYNCOUT pulses at the end of the sweep instead of at each increment
+    pub syncsel: bool,
+    /// SYNCOUT pin enabled
+    pub syncouten: bool,
+}
+
+/// Part of a frequency register that is written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrequencyPart {
+    /// Least significant bits
+    Lsb,
+    /// Most significant bits
+    Msb,
+}
+
+/// Decoded command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    /// Control register write
+    Control(Control),
+    /// Frequency register write
+    ///
+    /// The value has 14 bits on AD9833/AD9834/AD9837/AD9838 devices and
+    /// 16 bits on AD9832/AD9835 devices.
+    Frequency {
+        /// Frequency register
+        register: FrequencyRegister,
+        /// Written part of the register
+        part: FrequencyPart,
+        /// Written value
+        value: u16,
+    },
+    /// Phase register write (12-bit value)
+    Phase {
+        /// Phase register
+        register: PhaseRegister,
+        /// Written value
+        value: u16,
+    },
+    /// Byte loaded into the defer register (AD9832/AD9835)
+    ///
+    /// It is written into the register together with the next 16-bit write.
+    Defer {
+        /// Register address (4 bits)
+        address: u8,
+        /// Loaded byte
+        value: u8,
+    },
+    /// Phase register write (AD9832/AD9835, 12-bit value)
+    ExtendedPhase {
+        /// Phase register
+        register: ExtendedPhaseRegister,
+        /// Written value
+        value: u16,
+    },
+    /// Frequency and phase register selection (AD9832/AD9835)
+    ///
+    /// A register selection is `None` if the command does not change it.
+    Selection {
+        /// Selected frequency register
+        fselect: Option<FrequencyRegister>,
+        /// Selected phase register
+        pselect: Option<ExtendedPhaseRegister>,
+    },
+    /// Selection synchronization and source (AD9832/AD9835)
+    SyncSelsrc {
+        /// Pin selection synchronized with MCLK
+        sync: bool,
+        /// Selection through the SELECT commands instead of the pins
+        selsrc: bool,
+    },
+    /// Power-down and reset (AD9832/AD9835)
+    SleepResetClr {
+        /// DAC and internal clock powered down
+        sleep: bool,
+        /// Phase accumulator held in reset
+        reset: bool,
+        /// SYNC and SELSRC cleared
+        clr: bool,
+    },
+    /// Control register write (AD5930/AD5932)
+    SweepControl(SweepControl),
+    /// Start frequency write (AD5930/AD5932, 12-bit value)
+    StartFrequency {
+        /// Written part of the register
+        part: FrequencyPart,
+        /// Written value
+        value: u16,
+    },
+    /// Frequency increment write (AD5930/AD5932)
+    ///
+    /// The value has 12 bits for the LSBs and 11 bits for the MSBs.
+    FrequencyIncrement {
+        /// Written part of the register
+        part: FrequencyPart,
+        /// Written value
+        value: u16,
+        /// The sweep goes down. This is only written with the MSBs.
+        negative: bool,
+    },
+    /// Number of frequency increments write (AD5930/AD5932, 12-bit value)
+    Increments(u16),
+    /// Increment interval write (AD5930/AD5932)
+    IncrementInterval(IncrementInterval),
+    /// Reserved command (AD9832/AD9835) or register address (AD5930/AD5932)
+    Unknown(u16),
+}
+
+/// Decoder of the words sent to a device
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    variant: Variant,
+    b28: bool,
+    hlb: bool,
+    pending_lsb: Option<FrequencyRegister>,
+    defer: u8,
+}
+
+impl Decoder {
+    /// Create a new decoder for the given device variant.
+    ///
+    /// The decoder starts with B28 on, as the driver sets it on the first
+    /// write, and with an empty defer register.
+    pub fn new(variant: Variant) -> Self {
+        Decoder {
+            variant,
+            b28: true,
+            hlb: false,
+            pending_lsb: None,
+            defer: 0,
+        }
+    }
+
+    /// Decode a 16-bit word
+    pub fn decode(&mut self, word: u16) -> Command {
+        match self.variant {
+            Variant::Ad9832 | Variant::Ad9835 => self.decode_command(word),
+            Variant::Ad5930 | Variant::Ad5932 => self.decode_sweep(word),
+            Variant::Ad9833 | Variant::Ad9834 | Variant::Ad9837 | Variant::Ad9838 => {
+                self.decode_register(word)
+            }
+        }
+    }
+
+    /// Decode big-endian byte pairs
+    ///
+    /// A trailing byte is ignored.
+    pub fn decode_bytes<'a>(&'a mut self, bytes: &'a [u8]) -> impl Iterator<Item = Command> + 'a {
+        bytes
+            .chunks_exact(2)
+            .map(move |pair| self.decode(u16::from_be_bytes([pair[0], pair[1]])))
+    }
+
+    /// Decode a word of AD9833/AD9834/AD9837/AD9838 devices
+    fn decode_register(&mut self, word: u16) -> Command {
+        match word & (BitFlags::D15 | BitFlags::D14) {
+            0 => {
+                self.pending_lsb = None;
+                let control = self.decode_control(word);
+                self.b28 = control.b28;
+                self.hlb = control.hlb;
+                Command::Control(control)
+            }
+            BitFlags::D14 => self.decode_frequency(FrequencyRegister::F0, word),
+            BitFlags::D15 => self.decode_frequency(FrequencyRegister::F1, word),
+            _ => {
+                self.pending_lsb = None;
+                let register = if word & BitFlags::D13 != 0 {
+                    PhaseRegister::P1
+                } else {
+                    PhaseRegister::P0
+                };
+                Command::Phase {
+                    register,
+                    value: word & 0xFFF,
+                }
+            }
+        }
+    }
+
+    fn decode_control(&self, word: u16) -> Control {
+        let has_pins = self.variant.capabilities().sign_bit_output;
+        let is_set = |mask| word & mask != 0;
+        let optional = |mask| if has_pins { Some(is_set(mask)) } else { None };
+        let mut reserved = word & RESERVED;
+        if !has_pins {
+            reserved |= word & (BitFlags::PIN_SW | BitFlags::SIGN_PIB);
+        }
+        Control {
+            b28: is_set(BitFlags::B28),
+            hlb: is_set(BitFlags::HLB),
+            fselect: if is_set(BitFlags::FSELECT) {
+                FrequencyRegister::F1
+            } else {
+                FrequencyRegister::F0
+            },
+            pselect: if is_set(BitFlags::PSELECT) {
+                PhaseRegister::P1
+            } else {
+                PhaseRegister::P0
+            },
+            pin_sw: optional(BitFlags::PIN_SW),
+            reset: is_set(BitFlags::RESET),
+            sleep_mclk: is_set(BitFlags::SLEEP_MCLK),
+            sleep_dac: is_set(BitFlags::SLEEP_DAC),
+            opbiten: is_set(BitFlags::OPBITEN),
+            sign_pib: optional(BitFlags::SIGN_PIB),
+            div2: is_set(BitFlags::DIV2),
+            mode: is_set(BitFlags::MODE),
+            reserved,
+        }
+    }
+
+    fn decode_frequency(&mut self, register: FrequencyRegister, word: u16) -> Command {
+        let part = if self.b28 {
+            if self.pending_lsb == Some(register) {
+                self.pending_lsb = None;
+                FrequencyPart::Msb
+            } else {
+                self.pending_lsb = Some(register);
+                FrequencyPart::Lsb
+            }
+        } else if self.hlb {
+            FrequencyPart::Msb
+        } else {
+            FrequencyPart::Lsb
+        };
+        Command::Frequency {
+            register,
+            part,
+            value: word & 0x3FFF,
+        }
+    }
+
+    /// Decode a command of AD9832/AD9835 devices
+    fn decode_command(&mut self, word: u16) -> Command {
+        let is_set = |mask| word & mask != 0;
+        let address = ((word >> 8) & 0xF) as u8;
+        // SYNC/SELSRC and SLEEP/RESET/CLR only use C3 and C2 as command bits
+        let command = match word & (0b11 << 14) {
+            CommandBitFlags::CMD_SYNC_SELSRC => CommandBitFlags::CMD_SYNC_SELSRC,
+            CommandBitFlags::CMD_SLEEP_RESET_CLR => CommandBitFlags::CMD_SLEEP_RESET_CLR,
+            _ => word & (0b1111 << 12),
+        };
+        match command {
+            CommandBitFlags::CMD_PHASE_DEFER | CommandBitFlags::CMD_FREQ_DEFER => {
+                self.defer = word as u8;
+                Command::Defer {
+                    address,
+                    value: self.defer,
+                }
+            }
+            CommandBitFlags::CMD_FREQ_16 => Command::Frequency {
+                register: if address & 0b0100 != 0 {
+                    FrequencyRegister::F1
+                } else {
+                    FrequencyRegister::F0
+                },
+                part: if address & 0b0010 != 0 {
+                    FrequencyPart::Msb
+                } else {
+                    FrequencyPart::Lsb
+                },
+                value: u16::from_be_bytes([word as u8, self.defer]),
+            },
+            CommandBitFlags::CMD_PHASE_16 => Command::ExtendedPhase {
+                register: phase_register(address >> 1),
+                value: u16::from_be_bytes([word as u8, self.defer]) & 0xFFF,
+            },
+            command @ (CMD_PSELECT | CMD_FSELECT | CommandBitFlags::CMD_SELECT) => {
+                let fselect = if is_set(CommandBitFlags::FSELECT) {
+                    FrequencyRegister::F1
+                } else {
+                    FrequencyRegister::F0
+                };
+                let pselect = phase_register((word >> 9) as u8);
+                Command::Selection {
+                    fselect: Some(fselect).filter(|_| command != CMD_PSELECT),
+                    pselect: Some(pselect).filter(|_| command != CMD_FSELECT),
+                }
+            }
+            CommandBitFlags::CMD_SYNC_SELSRC => Command::SyncSelsrc {
+                sync: is_set(CommandBitFlags::CMD_SYNC),
+                selsrc: is_set(CommandBitFlags::CMD_SELSRC),
+            },
+            CommandBitFlags::CMD_SLEEP_RESET_CLR => Command::SleepResetClr {
+                sleep: is_set(CommandBitFlags::SLEEP),
+                reset: is_set(CommandBitFlags::RESET),
+                clr: is_set(CLR),
+            },
+            _ => Command::Unknown(word),
+        }
+    }
+
+    /// Decode a register write of AD5930/AD5932 devices
+    fn decode_sweep(&self, word: u16) -> Command {
+        let is_set = |mask| word & mask != 0;
+        let value = word & 0xFFF;
+        match word & (0b1111 << 12) {
+            SweepBitFlags::ADDR_CONTROL => Command::SweepControl(SweepControl {
+                b24: is_set(SweepBitFlags::B24),
+                dac_enable: is_set(SweepBitFlags::DAC_ENABLE),
+                sine_tri: is_set(SweepBitFlags::SINE_TRI),
+                msbouten: is_set(SweepBitFlags::MSBOUTEN),
+                cw_burst: match self.variant {
+                    Variant::Ad5930 => Some(is_set(SweepBitFlags::CW_BURST)),
+                    _ => None,
+                },
+                int_ext_incr: is_set(SweepBitFlags::INT_EXT_INCR),
+                syncsel: is_set(SweepBitFlags::SYNCSEL),
+                syncouten: is_set(SweepBitFlags::SYNCOUTEN),
+            }),
+            SweepBitFlags::ADDR_NINCR => Command::Increments(value),
+            SweepBitFlags::ADDR_DELTA_F_LSB => Command::FrequencyIncrement {
+                part: FrequencyPart::Lsb,
+                value,
+                negative: false,
+            },
+            SweepBitFlags::ADDR_DELTA_F_MSB => Command::FrequencyIncrement {
+                part: FrequencyPart::Msb,
+                value: value & !SweepBitFlags::DELTA_F_NEGATIVE,
+                negative: is_set(SweepBitFlags::DELTA_F_NEGATIVE),
+            },
+            SweepBitFlags::ADDR_FSTART_LSB => Command::StartFrequency {
+                part: FrequencyPart::Lsb,
+                value,
+            },
+            SweepBitFlags::ADDR_FSTART_MSB => Command::StartFrequency {
+                part: FrequencyPart::Msb,
+                value,
+            },
+            // The interval multiplier uses the LSB of the address
+            address => match address & (0b1110 << 12) {
+                SweepBitFlags::ADDR_TINT_OUTPUT_CYCLES => {
+                    increment_interval(IncrementIntervalBase::OutputCycles, word)
+                }
+                SweepBitFlags::ADDR_TINT_MCLK_PERIODS => {
+                    increment_interval(IncrementIntervalBase::MclkPeriods, word)
+                }
+                _ => Command::Unknown(word),
+            },
+        }
+    }
+}
+
+/// Phase register from the 2 LSBs of a selection or address
+fn phase_register(bits: u8) -> ExtendedPhaseRegister {
+    match bits & 0b11 {
+        0 => ExtendedPhaseRegister::P0,
+        1 => ExtendedPhaseRegister::P1,
+        2 => ExtendedPhaseRegister::P2,
+        _ => ExtendedPhaseRegister::P3,
+    }
+}
+
+fn increment_interval(base: IncrementIntervalBase, word: u16) -> Command {
+    let multiplier = match (word >> 11) & 0b11 {
+        0b00 => IncrementIntervalMultiplier::X1,
+        0b01 => IncrementIntervalMultiplier::X5,
+        0b10 => IncrementIntervalMultiplier::X100,
+        _ => IncrementIntervalMultiplier::X500,
+    };
+    Command::IncrementInterval(IncrementInterval {
+        base,
+        multiplier,
+        count: word & 0x7FF,
+    })
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+impl fmt::Display for Control {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "control: B28 {}, HLB {}, FSELECT={:?}, PSELECT={:?}",
+            on_off(self.b28),
+            on_off(self.hlb),
+            self.fselect,
+            self.pselect
+        )?;
+        if let Some(pin_sw) = self.pin_sw {
+            write!(f, ", PIN/SW {}", on_off(pin_sw))?;
+        }
+        write!(
+            f,
+            ", RESET {}, SLEEP1 {}, SLEEP12 {}, OPBITEN {}",
+            on_off(self.reset),
+            on_off(self.sleep_mclk),
+            on_off(self.sleep_dac),
+            on_off(self.opbiten)
+        )?;
+        if let Some(sign_pib) = self.sign_pib {
+            write!(f, ", SIGN/PIB {}", on_off(sign_pib))?;
+        }
+        write!(
+            f,
+            ", DIV2 {}, MODE {}",
+            on_off(self.div2),
+            on_off(self.mode)
+        )?;
+        if self.reserved != 0 {
+            write!(f, ", reserved bits set: {:#06X}", self.reserved)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SweepControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "control: B24 {}, DAC ENABLE {}, SINE/TRI {}, MSBOUTEN {}",
+            on_off(self.b24),
+            on_off(self.dac_enable),
+            on_off(self.sine_tri),
+            on_off(self.msbouten)
+        )?;
+        if let Some(cw_burst) = self.cw_burst {
+            write!(f, ", CW/BURST {}", on_off(cw_burst))?;
+        }
+        write!(
+            f,
+            ", INT/EXT INCR {}, SYNCSEL {}, SYNCOUTEN {}",
+            on_off(self.int_ext_incr),
+            on_off(self.syncsel),
+            on_off(self.syncouten)
+        )
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Control(control) => control.fmt(f),
+            Command::Frequency {
+                register,
+                part,
+                value,
+            } => {
+                let index = *register as u8;
+                write!(f, "FREQ{} {} = {:#06X}", index, part_name(*part), value)
+            }
+            Command::Phase { register, value } => {
+                write!(f, "PHASE{} = {}", *register as u8, value)
+            }
+            Command::Defer { address, value } => {
+                write!(f, "defer [{:#X}] = {:#04X}", address, value)
+            }
+            Command::ExtendedPhase { register, value } => {
+                write!(f, "PHASE{} = {}", *register as u8, value)
+            }
+            Command::Selection { fselect, pselect } => {
+                write!(f, "select:")?;
+                if let Some(fselect) = fselect {
+                    write!(f, " FSELECT={:?}", fselect)?;
+                }
+                if let Some(pselect) = pselect {
+                    write!(f, " PSELECT={:?}", pselect)?;
+                }
+                Ok(())
+            }
+            Command::SyncSelsrc { sync, selsrc } => {
+                write!(f, "SYNC {}, SELSRC {}", on_off(*sync), on_off(*selsrc))
+            }
+            Command::SleepResetClr { sleep, reset, clr } => write!(
+                f,
+                "SLEEP {}, RESET {}, CLR {}",
+                on_off(*sleep),
+                on_off(*reset),
+                on_off(*clr)
+            ),
+            Command::SweepControl(control) => control.fmt(f),
+            Command::StartFrequency { part, value } => {
+                write!(f, "FSTART {} = {:#05X}", part_name(*part), value)
+            }
+            Command::FrequencyIncrement {
+                part,
+                value,
+                negative,
+            } => {
+                write!(f, "DELTA_F {} = {:#05X}", part_name(*part), value)?;
+                if *negative {
+                    write!(f, ", negative")?;
+                }
+                Ok(())
+            }
+            Command::Increments(value) => write!(f, "NINCR = {}", value),
+            Command::IncrementInterval(interval) => {
+                let base = match interval.base {
+                    IncrementIntervalBase::OutputCycles => "output cycles",
+                    IncrementIntervalBase::MclkPeriods => "MCLK periods",
+                };
+                let multiplier = match interval.multiplier {
+                    IncrementIntervalMultiplier::X1 => 1,
+                    IncrementIntervalMultiplier::X5 => 5,
+                    IncrementIntervalMultiplier::X100 => 100,
+                    IncrementIntervalMultiplier::X500 => 500,
+                };
+                write!(f, "TINT = {} x{} {}", interval.count, multiplier, base)
+            }
+            Command::Unknown(word) => write!(f, "unknown command {:#06X}", word),
+        }
+    }
+}
+
+fn part_name(part: FrequencyPart) -> &'static str {
+    match part {
+        FrequencyPart::Lsb => "LSB",
+        FrequencyPart::Msb => "MSB",
+    }
+}
+
+/// Error parsing a capture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseError {
+    /// A value is not a valid hexadecimal byte or word (line number, starting at 1)
+    InvalidValue(usize),
+    /// The bytes cannot be paired into words
+    OddByteCount,
+    /// The CSV export does not contain any data
+    MissingData,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidValue(line) => write!(f, "invalid value in line {}", line),
+            ParseError::OddByteCount => write!(f, "odd number of bytes"),
+            ParseError::MissingData => write!(f, "no data found"),
+        }
+    }
+}
+
+/// Collects bytes and words into 16-bit words
+#[derive(Default)]
+struct Words {
+    words: Vec<u16>,
+    high_byte: Option<u8>,
+}
+
+impl Words {
+    /// Add a value with 1 to 2 hexadecimal digits (byte) or 3 to 4 digits (word).
+    fn push(&mut self, token: &str, line: usize) -> Result<(), ParseError> {
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        let value = u16::from_str_radix(digits, 16).map_err(|_| ParseError::InvalidValue(line))?;
+        match (digits.len(), self.high_byte.take()) {
+            (1..=2, None) => self.high_byte = Some(value as u8),
+            (1..=2, Some(high)) => self.words.push(u16::from_be_bytes([high, value as u8])),
+            (3..=4, None) => self.words.push(value),
+            _ => return Err(ParseError::InvalidValue(line)),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Vec<u16>, ParseError> {
+        if self.high_byte.is_some() {
+            return Err(ParseError::OddByteCount);
+        }
+        Ok(self.words)
+    }
+}
+
+/// Parse a hex dump into 16-bit words
+///
+/// Values are separated by whitespace or commas and can have a `0x` prefix.
+/// Values with up to 2 digits are bytes and are paired in big-endian order.
+/// Values with 3 or 4 digits are words. Anything after a `#` is ignored.
+///
+/// The output of `xxd` and `hexdump -C` is supported as well:
+/// - Anything up to a `:` in a line (like an `xxd` offset) is ignored. The
+///   ASCII column after the next two consecutive spaces is ignored as well.
+/// - A leading value with 5 to 8 digits (like a `hexdump -C` offset) is
+///   ignored. The ASCII column after a `|` is ignored as well.
+///
+/// Squeezed repeated lines (`*`) are not supported. Use `hexdump -v -C`.
+pub fn parse_hex(dump: &str) -> Result<Vec<u16>, ParseError> {
+    let mut words = Words::default();
+    for (index, line) in dump.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        let line = line.split('|').next().unwrap_or("");
+        let line = match line.split_once(':') {
+            Some((_, values)) => values.trim_start().split("  ").next().unwrap_or(""),
+            None => line,
+        };
+        let mut tokens = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .peekable();
+        let is_offset = |token: &&str| {
+            (5..=8).contains(&token.len()) && token.chars().all(|c| c.is_ascii_hexdigit())
+        };
+        tokens.next_if(is_offset);
+        for token in tokens {
+            words.push(token, index + 1)?;
+        }
+    }
+    words.finish()
+}
+
+/// Parse a sigrok/PulseView CSV export into 16-bit words
+///
+/// The values are taken from the first column whose header contains `MOSI`
+/// or, if there is no such column, from the last column. Rows where that
+/// column is empty are skipped. Quotes around the values are removed.
+/// PulseView annotation rows of the form `"SPI: MOSI data: 21"` are
+/// supported as well.
+pub fn parse_csv(csv: &str) -> Result<Vec<u16>, ParseError> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with(';'));
+    let (_, header) = lines.next().ok_or(ParseError::MissingData)?;
+    let header: Vec<&str> = header.split(',').map(unquote).collect();
+    let column = header
+        .iter()
+        .position(|name| {
+            name.as_bytes()
+                .windows(4)
+                .any(|window| window.eq_ignore_ascii_case(b"MOSI"))
+        })
+        .unwrap_or(header.len() - 1);
+    let mut words = Words::default();
+    let mut found = false;
+    for (index, line) in lines {
+        let cells: Vec<&str> = line.split(',').map(unquote).collect();
+        let cell = match cells.get(column) {
+            Some(cell) if !cell.is_empty() => *cell,
+            _ => continue,
+        };
+        // Annotation text like "SPI: MOSI data: 21"
+        let value = cell.rsplit(':').next().unwrap_or("").trim();
+        words.push(value, index + 1)?;
+        found = true;
+    }
+    if !found {
+        return Err(ParseError::MissingData);
+    }
+    words.finish()
+}
+
+fn unquote(cell: &str) -> &str {
+    cell.trim().trim_matches('"').trim()
+}
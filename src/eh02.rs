@@ -0,0 +1,94 @@
+//! Compatibility with `embedded-hal` 0.2
+//!
+//! [`Eh02Spi`] combines an SPI peripheral implementing the `embedded-hal`
+//! 0.2 `blocking::spi::Write` trait with a separate output pin connected to
+//! FSYNC, so that the driver can be used with HALs that do not implement
+//! `embedded-hal` 1.0 yet.
+//!
+//! FSYNC is set low before each 16-bit word and set high again afterwards,
+//! also when the SPI write fails.
+//!
+//! This module is available with the `eh02` feature.
+//!
+//! ```
+//! # use embedded_hal_02::{blocking::spi::Write, digital::v2::OutputPin};
+//! # struct Spi;
+//! # impl Write<u8> for Spi {
+//! #     type Error = ();
+//! #     fn write(&mut self, _words: &[u8]) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # struct Pin;
+//! # impl OutputPin for Pin {
+//! #     type Error = ();
+//! #     fn set_low(&mut self) -> Result<(), ()> { Ok(()) }
+//! #     fn set_high(&mut self) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # let (spi, fsync) = (Spi, Pin);
+//! use ad983x::{eh02::Eh02Spi, Ad983x, FrequencyRegister};
+//!
+//! let mut dds = Ad983x::new_ad9833(Eh02Spi::new(spi, fsync));
+//! dds.reset().unwrap();
+//! dds.set_frequency(FrequencyRegister::F0, 4724).unwrap();
+//! dds.enable().unwrap();
+//! // Get the SPI peripheral and the FSYNC pin back
+//! let (spi, fsync) = dds.destroy().destroy();
+//! ```
+
+use core::fmt::Debug;
+use embedded_hal::spi::ErrorKind;
+use embedded_hal_02::{blocking::spi::Write, digital::v2::OutputPin};
+
+use crate::WriteWord;
+
+/// `embedded-hal` 0.2 interface error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eh02Error<SpiE, PinE> {
+    /// SPI communication error
+    Spi(SpiE),
+    /// Error setting the FSYNC pin
+    Pin(PinE),
+}
+
+impl<SpiE: Debug, PinE: Debug> embedded_hal::spi::Error for Eh02Error<SpiE, PinE> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Eh02Error::Spi(_) => ErrorKind::Other,
+            Eh02Error::Pin(_) => ErrorKind::ChipSelectFault,
+        }
+    }
+}
+
+/// `embedded-hal` 0.2 SPI peripheral and FSYNC pin
+#[derive(Debug)]
+pub struct Eh02Spi<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+}
+
+impl<SPI, CS> Eh02Spi<SPI, CS> {
+    /// Create a new instance from an SPI peripheral and the pin connected to FSYNC.
+    pub fn new(spi: SPI, cs: CS) -> Self {
+        Eh02Spi { spi, cs }
+    }
+
+    /// Destroy the instance and return the SPI peripheral and the FSYNC pin.
+    pub fn destroy(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+impl<SPI, CS, SpiE, PinE> WriteWord for Eh02Spi<SPI, CS>
+where
+    SPI: Write<u8, Error = SpiE>,
+    CS: OutputPin<Error = PinE>,
+{
+    type Error = Eh02Error<SpiE, PinE>;
+
+    fn write_word(&mut self, word: u16) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Eh02Error::Pin)?;
+        let result = self.spi.write(&word.to_be_bytes()).map_err(Eh02Error::Spi);
+        let cs_result = self.cs.set_high().map_err(Eh02Error::Pin);
+        result?;
+        cs_result
+    }
+}
@@ -0,0 +1,319 @@
+//! Software emulator of the AD9833/AD9834/AD9837/AD9838 serial interface
+//!
+//! The emulator implements `SpiDevice` so it can be given to the driver in
+//! place of a real device. Every 16-bit word is decoded like the device
+//! does it and the resulting register file can be queried at any time.
+//! Protocol misuse is recorded as a [`Violation`].
+//!
+//! This module is available with the `emulator` feature.
+//!
+//! ```
+//! use ad983x::{emulator::Emulator, Ad983x, FrequencyRegister, OutputWaveform, Variant};
+//!
+//! let emulator = Emulator::new(Variant::Ad9833).unwrap();
+//! let mut dds = Ad983x::new_ad9833(emulator.clone());
+//! dds.reset().unwrap();
+//! dds.set_frequency(FrequencyRegister::F1, 4724).unwrap();
+//! dds.select_frequency(FrequencyRegister::F1).unwrap();
+//! dds.enable().unwrap();
+//!
+//! assert_eq!(4724, emulator.frequency(FrequencyRegister::F1));
+//! assert_eq!(FrequencyRegister::F1, emulator.selected_frequency());
+//! assert_eq!(OutputWaveform::Sinusoidal, emulator.output_waveform());
+//! assert!(emulator.violations().is_empty());
+//! ```
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::convert::Infallible;
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+
+use crate::{
+    BitFlags, FrequencyRegister, OutputWaveform, PhaseRegister, PoweredDown, SignBitOutput, Variant,
+};
+
+/// Reserved control bits on all devices
+const RESERVED: u16 = 1 << 2 | 1;
+/// Reserved control bits on AD9833/AD9837 devices only
+const RESERVED_AD9833: u16 = BitFlags::PIN_SW | BitFlags::SIGN_PIB;
+
+/// Protocol misuse detected by the emulator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    /// A transaction ended in the middle of a 16-bit word.
+    /// The incomplete word was ignored.
+    IncompleteWord,
+    /// Only the 14 LSBs of a 28-bit frequency write were written
+    /// before writing to another register.
+    IncompleteFrequencyWrite(FrequencyRegister),
+    /// A control word set reserved bits.
+    ReservedBitSet(u16),
+    /// A control word selected a reserved output configuration
+    /// (OPBITEN and MODE set at the same time).
+    ReservedOutputConfiguration(u16),
+}
+
+#[derive(Debug)]
+struct State {
+    variant: Variant,
+    control: u16,
+    frequency: [u32; 2],
+    phase: [u16; 2],
+    pending_lsb: Option<(FrequencyRegister, u16)>,
+    violations: Vec<Violation>,
+}
+
+/// Emulated AD9833/AD9834/AD9837/AD9838 device
+///
+/// Clones share the same device state, so a clone can be kept to query the
+/// state while the driver owns the original.
+#[derive(Debug, Clone)]
+pub struct Emulator {
+    state: Rc<RefCell<State>>,
+}
+
+impl Emulator {
+    /// Create a new emulated device.
+    ///
+    /// Only AD9833, AD9834, AD9837 and AD9838 devices can be emulated.
+    /// Returns `None` for other variants.
+    /// All registers are zero after creation.
+    pub fn new(variant: Variant) -> Option<Self> {
+        match variant {
+            Variant::Ad9833 | Variant::Ad9834 | Variant::Ad9837 | Variant::Ad9838 => {
+                Some(Emulator {
+                    state: Rc::new(RefCell::new(State {
+                        variant,
+                        control: 0,
+                        frequency: [0; 2],
+                        phase: [0; 2],
+                        pending_lsb: None,
+                        violations: Vec::new(),
+                    })),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the emulated device variant
+    pub fn variant(&self) -> Variant {
+        self.state.borrow().variant
+    }
+
+    /// Get the current control register value (D13-D0)
+    pub fn control(&self) -> u16 {
+        self.state.borrow().control
+    }
+
+    /// Get the current 28-bit value of a frequency register
+    pub fn frequency(&self, register: FrequencyRegister) -> u32 {
+        self.state.borrow().frequency[register as usize]
+    }
+
+    /// Get the current 12-bit value of a phase register
+    pub fn phase(&self, register: PhaseRegister) -> u16 {
+        self.state.borrow().phase[register as usize]
+    }
+
+    /// Get the frequency register used for the output
+    pub fn selected_frequency(&self) -> FrequencyRegister {
+        if self.control() & BitFlags::FSELECT != 0 {
+            FrequencyRegister::F1
+        } else {
+            FrequencyRegister::F0
+        }
+    }
+
+    /// Get the phase register used for the output
+    pub fn selected_phase(&self) -> PhaseRegister {
+        if self.control() & BitFlags::PSELECT != 0 {
+            PhaseRegister::P1
+        } else {
+            PhaseRegister::P0
+        }
+    }
+
+    /// Get the waveform on the VOUT pin
+    ///
+    /// On AD9834/AD9838 devices the square wave is output on the SIGN BIT OUT
+    /// pin instead. See [`sign_bit_output()`](#method.sign_bit_output).
+    pub fn output_waveform(&self) -> OutputWaveform {
+        let control = self.control();
+        let vout_square = !self.has_sign_bit_output() && control & BitFlags::OPBITEN != 0;
+        if vout_square {
+            if control & BitFlags::DIV2 != 0 {
+                OutputWaveform::SquareMsbOfDac
+            } else {
+                OutputWaveform::SquareMsbOfDacDiv2
+            }
+        } else if control & BitFlags::MODE != 0 {
+            OutputWaveform::Triangle
+        } else {
+            OutputWaveform::Sinusoidal
+        }
+    }
+
+    /// Get the SIGN BIT OUT pin configuration
+    ///
+    /// This is always `SignBitOutput::Disabled` on AD9833/AD9837 devices.
+    pub fn sign_bit_output(&self) -> SignBitOutput {
+        let control = self.control();
+        if !self.has_sign_bit_output() || control & BitFlags::OPBITEN == 0 {
+            SignBitOutput::Disabled
+        } else if control & BitFlags::SIGN_PIB != 0 {
+            SignBitOutput::Comparator
+        } else if control & BitFlags::DIV2 != 0 {
+            SignBitOutput::SquareMsbOfDac
+        } else {
+            SignBitOutput::SquareMsbOfDacDiv2
+        }
+    }
+
+    /// Get the powered-down state of the device parts
+    pub fn powered_down(&self) -> PoweredDown {
+        let control = self.control();
+        match (
+            control & BitFlags::SLEEP_MCLK != 0,
+            control & BitFlags::SLEEP_DAC != 0,
+        ) {
+            (false, false) => PoweredDown::Nothing,
+            (false, true) => PoweredDown::Dac,
+            (true, false) => PoweredDown::InternalClock,
+            (true, true) => PoweredDown::DacAndInternalClock,
+        }
+    }
+
+    /// Whether the internal registers are held in reset
+    pub fn is_reset(&self) -> bool {
+        self.control() & BitFlags::RESET != 0
+    }
+
+    /// Get the protocol violations detected so far
+    ///
+    /// This includes a 28-bit frequency write still waiting for its 14 MSBs.
+    pub fn violations(&self) -> Vec<Violation> {
+        let state = self.state.borrow();
+        let mut violations = state.violations.clone();
+        if let Some((register, _)) = state.pending_lsb {
+            violations.push(Violation::IncompleteFrequencyWrite(register));
+        }
+        violations
+    }
+
+    /// Forget the protocol violations detected so far
+    pub fn clear_violations(&mut self) {
+        self.state.borrow_mut().violations.clear();
+    }
+
+    fn has_sign_bit_output(&self) -> bool {
+        self.variant().capabilities().sign_bit_output
+    }
+}
+
+impl State {
+    fn decode(&mut self, word: u16) {
+        match word & (BitFlags::D15 | BitFlags::D14) {
+            0 => self.write_control(word & 0x3FFF),
+            BitFlags::D14 => self.write_frequency(FrequencyRegister::F0, word & 0x3FFF),
+            BitFlags::D15 => self.write_frequency(FrequencyRegister::F1, word & 0x3FFF),
+            _ => self.write_phase(word),
+        }
+    }
+
+    fn write_control(&mut self, control: u16) {
+        self.finish_pending_write();
+        let mut reserved = RESERVED;
+        if !self.variant.capabilities().sign_bit_output {
+            reserved |= RESERVED_AD9833;
+        }
+        if control & reserved != 0 {
+            self.violations.push(Violation::ReservedBitSet(control));
+        }
+        if control & BitFlags::OPBITEN != 0 && control & BitFlags::MODE != 0 {
+            self.violations
+                .push(Violation::ReservedOutputConfiguration(control));
+        }
+        self.control = control;
+    }
+
+    fn write_frequency(&mut self, register: FrequencyRegister, value: u16) {
+        let index = register as usize;
+        let value = u32::from(value);
+        if self.control & BitFlags::B28 != 0 {
+            match self.pending_lsb.take() {
+                Some((pending, lsb)) if pending == register => {
+                    self.frequency[index] = (value << 14) | u32::from(lsb);
+                }
+                Some((pending, _)) => {
+                    self.violations
+                        .push(Violation::IncompleteFrequencyWrite(pending));
+                    self.pending_lsb = Some((register, value as u16));
+                }
+                None => self.pending_lsb = Some((register, value as u16)),
+            }
+        } else if self.control & BitFlags::HLB != 0 {
+            self.finish_pending_write();
+            self.frequency[index] = (self.frequency[index] & 0x3FFF) | (value << 14);
+        } else {
+            self.finish_pending_write();
+            self.frequency[index] = (self.frequency[index] & !0x3FFF) | value;
+        }
+    }
+
+    fn write_phase(&mut self, word: u16) {
+        self.finish_pending_write();
+        let register = if word & BitFlags::D13 != 0 {
+            PhaseRegister::P1
+        } else {
+            PhaseRegister::P0
+        };
+        self.phase[register as usize] = word & 0xFFF;
+    }
+
+    fn finish_pending_write(&mut self) {
+        if let Some((register, _)) = self.pending_lsb.take() {
+            self.violations
+                .push(Violation::IncompleteFrequencyWrite(register));
+        }
+    }
+}
+
+impl ErrorType for Emulator {
+    type Error = Infallible;
+}
+
+impl SpiDevice for Emulator {
+    /// The devices do not have a data output. Reading clocks out zeros.
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let mut bytes = Vec::new();
+        for operation in operations {
+            match operation {
+                Operation::Read(words) => {
+                    bytes.resize(bytes.len() + words.len(), 0);
+                    words.fill(0);
+                }
+                Operation::Write(words) => bytes.extend_from_slice(words),
+                Operation::Transfer(read, write) => {
+                    bytes.extend_from_slice(write);
+                    read.fill(0);
+                }
+                Operation::TransferInPlace(words) => {
+                    bytes.extend_from_slice(words);
+                    words.fill(0);
+                }
+                Operation::DelayNs(_) => (),
+            }
+        }
+        let mut state = self.state.borrow_mut();
+        let words = bytes.chunks_exact(2);
+        if !words.remainder().is_empty() {
+            state.violations.push(Violation::IncompleteWord);
+        }
+        for word in words {
+            state.decode(u16::from_be_bytes([word[0], word[1]]));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+//! Transport-independent command encoder
+//!
+//! The [`Encoder`] turns operations on an AD9833, AD9834, AD9837 or AD9838
+//! device into the 16-bit words that need to be sent to it. It keeps track
+//! of the control register like the driver does, so that the B28/HLB
+//! sequencing is respected.
+//!
+//! This is useful to drive the device through a transport that does not
+//! implement `SpiDevice`, like a DMA engine or a PIO state machine.
+//! The words are sent MSB first. See [`Words::bytes()`].
+//!
+//! ```
+//! use ad983x::encoder::Encoder;
+//! use ad983x::{ControlWord, FrequencyRegister, PhaseRegister, Variant};
+//!
+//! let mut encoder = Encoder::new(Variant::Ad9833).unwrap();
+//! let words = encoder.set_frequency(FrequencyRegister::F0, 0x1274).unwrap();
+//! assert_eq!(&[0x2100, 0x5274, 0x4000], words.as_slice());
+//! let words = encoder.set_phase(PhaseRegister::P0, 1024).unwrap();
+//! assert_eq!(&[0xC400], words.as_slice());
+//! // Triangle output
+//! let control = encoder.control_word().reset(false).mode(true);
+//! let words = encoder.write_control_word(control).unwrap();
+//! let mut bytes = [0; 2];
+//! for (byte, value) in bytes.iter_mut().zip(words.bytes()) {
+//!     *byte = value;
+//! }
+//! assert_eq!([0x20, 0x02], bytes);
+//! ```
+
+use core::convert::Infallible;
+
+use crate::{
+    common::{check_control_word, check_value_fits},
+    BitFlags, Config, ControlSource, ControlWord, Error, FrequencyRegister, OutputWaveform,
+    PhaseRegister, PoweredDown, SignBitOutput, Variant,
+};
+
+/// Words to be sent to the device for one operation
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Words {
+    words: [u16; 3],
+    len: usize,
+}
+
+impl Words {
+    fn push(&mut self, word: u16) {
+        self.words[self.len] = word;
+        self.len += 1;
+    }
+
+    /// Get the words in the order they need to be sent
+    pub fn as_slice(&self) -> &[u16] {
+        &self.words[..self.len]
+    }
+
+    /// Get the words as big-endian bytes in the order they need to be sent
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.as_slice().iter().flat_map(|word| word.to_be_bytes())
+    }
+}
+
+/// Command encoder for AD9833/AD9834/AD9837/AD9838 devices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Encoder {
+    variant: Variant,
+    control: ControlWord,
+}
+
+impl Encoder {
+    /// Create a new encoder for the given device variant.
+    ///
+    /// The control register starts with RESET set like in the driver.
+    /// Returns `None` for variants other than AD9833/AD9834/AD9837/AD9838.
+    pub fn new(variant: Variant) -> Option<Self> {
+        match variant {
+            Variant::Ad9833 | Variant::Ad9834 | Variant::Ad9837 | Variant::Ad9838 => {
+                Some(Encoder {
+                    variant,
+                    control: ControlWord::new().reset(true),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the control register value as known by the encoder
+    pub fn control_word(&self) -> ControlWord {
+        self.control
+    }
+
+    /// Encode a write of the control register value.
+    ///
+    /// Returns `Error::Unsupported` if setting PIN/SW or SIGN/PIB on
+    /// AD9833/AD9837 devices and `Error::UnsupportedWaveform` if setting MODE
+    /// together with OPBITEN.
+    pub fn write_control_word(&mut self, word: ControlWord) -> Result<Words, Error<Infallible>> {
+        check_control_word(self.variant, word)?;
+        let mut words = Words::default();
+        self.push_control(&mut words, word);
+        Ok(words)
+    }
+
+    /// Encode a reset of the internal registers, leaving the device disabled.
+    pub fn reset(&mut self) -> Words {
+        self.disable()
+    }
+
+    /// Encode disabling the device (enable reset)
+    pub fn disable(&mut self) -> Words {
+        let mut words = Words::default();
+        self.push_control(&mut words, self.control.reset(true));
+        words
+    }
+
+    /// Encode enabling the device (disable reset)
+    pub fn enable(&mut self) -> Words {
+        let mut words = Words::default();
+        self.push_control(&mut words, self.control.reset(false));
+        words
+    }
+
+    /// Encode setting the frequency as a 28-bit word
+    ///
+    /// The control register is written first if the 28-bit mode is not used.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 28 bits.
+    pub fn set_frequency(
+        &mut self,
+        register: FrequencyRegister,
+        value: u32,
+    ) -> Result<Words, Error<Infallible>> {
+        check_value_fits(value, 28)?;
+        let mut words = Words::default();
+        self.push_control_if_different(&mut words, self.control.b28(true));
+        let [lsb, msb] = frequency_words(register, value);
+        words.push(lsb);
+        words.push(msb);
+        Ok(words)
+    }
+
+    /// Encode setting the frequency 14-bit MSBs
+    ///
+    /// The control register is written first if the 28-bit mode is used.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 14 bits.
+    pub fn set_frequency_msb(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+    ) -> Result<Words, Error<Infallible>> {
+        self.set_frequency_half(register, value, true)
+    }
+
+    /// Encode setting the frequency 14-bit LSBs
+    ///
+    /// The control register is written first if the 28-bit mode is used.
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 14 bits.
+    pub fn set_frequency_lsb(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+    ) -> Result<Words, Error<Infallible>> {
+        self.set_frequency_half(register, value, false)
+    }
+
+    fn set_frequency_half(
+        &mut self,
+        register: FrequencyRegister,
+        value: u16,
+        msb: bool,
+    ) -> Result<Words, Error<Infallible>> {
+        check_value_fits(value, 14)?;
+        let mut words = Words::default();
+        self.push_control_if_different(&mut words, self.control.b28(false).hlb(msb));
+        words.push(frequency_half_word(register, value));
+        Ok(words)
+    }
+
+    /// Encode selecting the frequency register that is used
+    pub fn select_frequency(&mut self, register: FrequencyRegister) -> Words {
+        let mut words = Words::default();
+        self.push_control(&mut words, self.control.fselect(register));
+        words
+    }
+
+    /// Encode setting a phase register (12-bit value)
+    ///
+    /// Returns `Error::ValueTooWide` if providing a value that does not fit in 12 bits.
+    pub fn set_phase(
+        &mut self,
+        register: PhaseRegister,
+        value: u16,
+    ) -> Result<Words, Error<Infallible>> {
+        check_value_fits(value, 12)?;
+        let mut words = Words::default();
+        words.push(phase_word(register, value));
+        Ok(words)
+    }
+
+    /// Encode selecting the phase register that is used
+    pub fn select_phase(&mut self, register: PhaseRegister) -> Words {
+        let mut words = Words::default();
+        self.push_control(&mut words, self.control.pselect(register));
+        words
+    }
+
+    /// Encode setting the device parts powered-down state
+    pub fn set_powered_down(&mut self, config: PoweredDown) -> Words {
+        let control = self.config().with_powered_down(config);
+        let mut words = Words::default();
+        self.push_config(&mut words, control);
+        words
+    }
+
+    /// Encode setting the output waveform
+    ///
+    /// Returns `Error::UnsupportedWaveform` for `SquareMsbOfDac` and
+    /// `SquareMsbOfDacDiv2` on AD9834/AD9838 devices.
+    pub fn set_output_waveform(
+        &mut self,
+        waveform: OutputWaveform,
+    ) -> Result<Words, Error<Infallible>> {
+        let control = if self.variant.capabilities().pin_control {
+            self.config()
+                .with_ad9834_waveform(waveform)
+                .ok_or(Error::UnsupportedWaveform(waveform))?
+        } else {
+            self.config().with_ad9833_waveform(waveform)
+        };
+        let mut words = Words::default();
+        self.push_config(&mut words, control);
+        Ok(words)
+    }
+
+    /// Encode setting the digital output (only available on AD9834/AD9838 devices)
+    ///
+    /// Returns `Error::Unsupported` on AD9833/AD9837 devices.
+    pub fn set_sign_bit_output(
+        &mut self,
+        configuration: SignBitOutput,
+    ) -> Result<Words, Error<Infallible>> {
+        if !self.variant.capabilities().pin_control {
+            return Err(Error::Unsupported);
+        }
+        let control = self.config().with_sign_bit_output(configuration);
+        let mut words = Words::default();
+        self.push_config(&mut words, control);
+        Ok(words)
+    }
+
+    /// Encode setting the control source (only available on AD9834/AD9838 devices)
+    ///
+    /// Returns `Error::Unsupported` on AD9833/AD9837 devices.
+    pub fn set_control_source(
+        &mut self,
+        source: ControlSource,
+    ) -> Result<Words, Error<Infallible>> {
+        if !self.variant.capabilities().pin_control {
+            return Err(Error::Unsupported);
+        }
+        let control = self.config().with_control_source(source);
+        let mut words = Words::default();
+        self.push_config(&mut words, control);
+        Ok(words)
+    }
+
+    fn config(&self) -> Config {
+        Config {
+            bits: self.control.bits(),
+        }
+    }
+
+    fn push_config(&mut self, words: &mut Words, config: Config) {
+        self.push_control(words, ControlWord { bits: config.bits });
+    }
+
+    fn push_control(&mut self, words: &mut Words, control: ControlWord) {
+        words.push(control_payload(control.bits()));
+        self.control = control;
+    }
+
+    fn push_control_if_different(&mut self, words: &mut Words, control: ControlWord) {
+        if control != self.control {
+            self.push_control(words, control);
+        }
+    }
+}
+
+pub(crate) fn frequency_register_bits(register: FrequencyRegister) -> u16 {
+    match register {
+        FrequencyRegister::F0 => BitFlags::D14,
+        FrequencyRegister::F1 => BitFlags::D15,
+    }
+}
+
+/// LSB and MSB words of a 28-bit frequency register write
+pub(crate) fn frequency_words(register: FrequencyRegister, value: u32) -> [u16; 2] {
+    let reg = frequency_register_bits(register);
+    let lsb = value & ((1 << 14) - 1);
+    let msb = value >> 14;
+    [reg | lsb as u16, reg | msb as u16]
+}
+
+/// Word of a 14-bit frequency register write
+pub(crate) fn frequency_half_word(register: FrequencyRegister, value: u16) -> u16 {
+    frequency_register_bits(register) | value
+}
+
+pub(crate) fn phase_word(register: PhaseRegister, value: u16) -> u16 {
+    let value = value | BitFlags::D14 | BitFlags::D15;
+    match register {
+        PhaseRegister::P0 => value,
+        PhaseRegister::P1 => value | BitFlags::D13,
+    }
+}
+
+pub(crate) fn control_payload(bits: u16) -> u16 {
+    bits & 0b0011_1111_1111_1111
+}
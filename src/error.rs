@@ -0,0 +1,50 @@
+use core::fmt;
+use embedded_hal::spi::ErrorKind;
+
+use crate::Error;
+
+impl<E> Error<E>
+where
+    E: embedded_hal::spi::Error,
+{
+    /// Get the kind of the SPI communication error.
+    ///
+    /// Returns `None` if this is not an SPI communication error.
+    pub fn spi_error_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Error::Spi(e) => Some(e.kind()),
+            _ => None,
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Spi(e) => write!(f, "SPI communication error: {:?}", e),
+            Error::ValueTooWide { bits } => write!(f, "value does not fit in {} bits", bits),
+            Error::OutOfRange { min, max } => {
+                write!(f, "value is outside of the range {} to {}", min, max)
+            }
+            Error::UnsupportedWaveform(waveform) => {
+                write!(
+                    f,
+                    "output waveform {:?} is not available on the device",
+                    waveform
+                )
+            }
+            Error::InvalidMclkFrequency { max_hz } => {
+                write!(f, "MCLK frequency must be between 1 Hz and {} Hz", max_hz)
+            }
+            Error::MclkFrequencyNotSet => write!(f, "no MCLK frequency has been configured"),
+            Error::FrequencyOutOfRange { max_hz } => {
+                write!(f, "frequency must be between 0 Hz and {} Hz", max_hz)
+            }
+            Error::Unsupported => write!(f, "operation not supported by the device"),
+            Error::InvalidChannel { index } => write!(f, "there is no channel {}", index),
+        }
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl<E: fmt::Debug> core::error::Error for Error<E> {}
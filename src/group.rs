@@ -0,0 +1,238 @@
+//! Synchronized start of several devices
+//!
+//! A [`SyncGroup`] loads the registers of several AD9833/AD9834/AD9837/AD9838
+//! devices sharing the same MCLK while each of them is held in reset and
+//! then releases the reset of all of them at once, so that their outputs
+//! start phase-aligned.
+//!
+//! The reset can be released in two ways:
+//! - With a single control register write through a broadcast interface
+//!   that asserts the FSYNC line of every device at the same time.
+//!   See [`SyncGroup::start()`]. The control register settings of all the
+//!   devices must then be the same.
+//! - Through the RESET pins of AD9834/AD9838 devices tied to a single output
+//!   pin. See [`SyncGroup::load_with_reset_pin()`] and
+//!   [`SyncGroup::start_with_reset_pin()`].
+//!
+//! **Note:** loading the devices with the RESET pin selects the hardware pin
+//! control source on every device and it stays selected after the start.
+//! The FSELECT, PSELECT, RESET and SLEEP pins then keep controlling the
+//! devices until `set_control_source(ControlSource::Software)` is called on
+//! each of them. The RESET bit of the control register is kept clear, so the
+//! devices keep running when switching back to software control.
+//!
+//! The broadcast interface can also be used to send identical words to all
+//! devices. See [`SyncGroup::broadcast()`].
+//!
+//! ```
+//! # use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+//! # struct Spi;
+//! # impl ErrorType for Spi { type Error = core::convert::Infallible; }
+//! # impl SpiDevice for Spi {
+//! #     fn transaction(&mut self, _: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let (spi0, spi1, mut broadcast) = (Spi, Spi, Spi);
+//! use ad983x::{group::SyncGroup, Ad983x, FrequencyRegister, PhaseRegister};
+//!
+//! let mut devices = [Ad983x::new_ad9833(spi0), Ad983x::new_ad9833(spi1)];
+//! let mut group = SyncGroup::new(&mut devices);
+//! group
+//!     .load(|index, dds| {
+//!         dds.set_frequency(FrequencyRegister::F0, 4724)?;
+//!         // Output the second device in quadrature
+//!         dds.set_phase(PhaseRegister::P0, index as u16 * 1024)
+//!     })
+//!     .unwrap();
+//! group.start(&mut broadcast).unwrap();
+//! ```
+
+use core::convert::Infallible;
+use embedded_hal::digital::OutputPin;
+
+use crate::{encoder, marker, private, Ad983x, BitFlags, Config, ControlSource, Error, WriteWord};
+
+/// Group operation error
+#[derive(Debug)]
+pub enum GroupError<E, PinE = Infallible> {
+    /// Error on the device with the given index
+    Device {
+        /// Index of the device in the group
+        index: usize,
+        /// Error returned by the device
+        error: Error<E>,
+    },
+    /// SPI communication error on the broadcast interface
+    Broadcast(E),
+    /// The control register settings of the device with the given index
+    /// differ from the ones of the first device.
+    ControlMismatch {
+        /// Index of the device in the group
+        index: usize,
+    },
+    /// Error setting the RESET pin
+    Pin(PinE),
+}
+
+/// Group of devices started in sync
+#[derive(Debug)]
+pub struct SyncGroup<'a, DEV, IC> {
+    devices: &'a mut [Ad983x<DEV, IC>],
+}
+
+impl<'a, DEV, IC> SyncGroup<'a, DEV, IC> {
+    /// Create a new group of devices.
+    pub fn new(devices: &'a mut [Ad983x<DEV, IC>]) -> Self {
+        SyncGroup { devices }
+    }
+
+    /// Get the devices in the group
+    pub fn devices(&mut self) -> &mut [Ad983x<DEV, IC>] {
+        self.devices
+    }
+
+    /// Destroy the group and return the devices.
+    pub fn destroy(self) -> &'a mut [Ad983x<DEV, IC>] {
+        self.devices
+    }
+}
+
+impl<'a, DEV, IC, E> SyncGroup<'a, DEV, IC>
+where
+    DEV: WriteWord<Error = E>,
+    IC: private::Ad983xFamily,
+{
+    /// Hold every device in reset and load its registers.
+    ///
+    /// The closure is called with the index and the driver of each device
+    /// after the reset has been enabled. If it disables the reset, the reset
+    /// is enabled again afterwards.
+    pub fn load<F>(&mut self, mut load: F) -> Result<(), GroupError<E>>
+    where
+        F: FnMut(usize, &mut Ad983x<DEV, IC>) -> Result<(), Error<E>>,
+    {
+        trace_op!("group load {} devices", self.devices.len());
+        for (index, dds) in self.devices.iter_mut().enumerate() {
+            let result = dds.reset().and_then(|_| load(index, dds)).and_then(|_| {
+                let control = dds.control.with_high(BitFlags::RESET);
+                dds.write_control_if_different(control)
+            });
+            result.map_err(|error| GroupError::Device { index, error })?;
+        }
+        Ok(())
+    }
+
+    /// Release the reset of all devices with a single write through the
+    /// broadcast interface.
+    ///
+    /// Returns `GroupError::ControlMismatch` before writing anything if the
+    /// control register settings of the devices differ apart from the reset.
+    pub fn start<B>(&mut self, broadcast: &mut B) -> Result<(), GroupError<E>>
+    where
+        B: WriteWord<Error = E>,
+    {
+        let control = match self.devices.first() {
+            Some(dds) => dds.control.with_low(BitFlags::RESET),
+            None => return Ok(()),
+        };
+        let mismatch = self
+            .devices
+            .iter()
+            .position(|dds| dds.control.with_low(BitFlags::RESET) != control);
+        if let Some(index) = mismatch {
+            return Err(GroupError::ControlMismatch { index });
+        }
+        trace_op!("group start");
+        self.broadcast(broadcast, &[encoder::control_payload(control.bits)])
+    }
+
+    /// Send identical words to all devices through the broadcast interface.
+    ///
+    /// Control register words update the driver state of every device and
+    /// bring them back in sync. After an SPI communication error all devices
+    /// are considered desynchronized.
+    pub fn broadcast<B>(&mut self, broadcast: &mut B, words: &[u16]) -> Result<(), GroupError<E>>
+    where
+        B: WriteWord<Error = E>,
+    {
+        for &word in words {
+            trace_word!("broadcast word {:#06x}", word);
+            if let Err(e) = broadcast.write_word(word) {
+                for dds in self.devices.iter_mut() {
+                    dds.desynchronized = true;
+                }
+                return Err(GroupError::Broadcast(e));
+            }
+            if word & (BitFlags::D15 | BitFlags::D14) == 0 {
+                for dds in self.devices.iter_mut() {
+                    dds.control = Config { bits: word };
+                    dds.desynchronized = false;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, DEV, E> SyncGroup<'a, DEV, marker::Ad9834Ad9838>
+where
+    DEV: WriteWord<Error = E>,
+{
+    /// Hold every device in reset through the RESET pin and load its registers.
+    ///
+    /// The pin is set high and the hardware pin control source is selected
+    /// on every device before calling the closure with the index and the
+    /// driver of each device. The FSELECT, PSELECT and SLEEP pins are then
+    /// used as well. The RESET bit of the control register is cleared, also
+    /// if the closure sets it, as the reset is held through the pin.
+    ///
+    /// **Note:** the hardware pin control source stays selected after
+    /// [`start_with_reset_pin()`](SyncGroup::start_with_reset_pin). Call
+    /// `set_control_source(ControlSource::Software)` on each device to
+    /// control it through the control register again. The devices keep
+    /// running then.
+    pub fn load_with_reset_pin<P, F>(
+        &mut self,
+        reset_pin: &mut P,
+        mut load: F,
+    ) -> Result<(), GroupError<E, P::Error>>
+    where
+        P: OutputPin,
+        F: FnMut(usize, &mut Ad983x<DEV, marker::Ad9834Ad9838>) -> Result<(), Error<E>>,
+    {
+        trace_op!("group load {} devices with reset pin", self.devices.len());
+        reset_pin.set_high().map_err(GroupError::Pin)?;
+        for (index, dds) in self.devices.iter_mut().enumerate() {
+            let control = dds
+                .control
+                .with_control_source(ControlSource::HardwarePins)
+                .with_low(BitFlags::RESET);
+            dds.write_control(control)
+                .and_then(|_| load(index, dds))
+                .and_then(|_| {
+                    let control = dds.control.with_low(BitFlags::RESET);
+                    dds.write_control_if_different(control)
+                })
+                .map_err(|error| GroupError::Device { index, error })?;
+        }
+        Ok(())
+    }
+
+    /// Release the reset of all devices through the RESET pin.
+    ///
+    /// **Note:** the hardware pin control source selected by
+    /// [`load_with_reset_pin()`](SyncGroup::load_with_reset_pin) is not
+    /// restored. The devices keep following the FSELECT, PSELECT, RESET and
+    /// SLEEP pins.
+    pub fn start_with_reset_pin<P>(
+        &mut self,
+        reset_pin: &mut P,
+    ) -> Result<(), GroupError<E, P::Error>>
+    where
+        P: OutputPin,
+    {
+        trace_op!("group start with reset pin");
+        reset_pin.set_low().map_err(GroupError::Pin)
+    }
+}
@@ -0,0 +1,69 @@
+use embedded_hal::spi::SpiDevice;
+
+/// Interface used to send 16-bit words to the device
+///
+/// This is implemented for any `SpiDevice` using 8-bit words, which sends
+/// each word as two bytes MSB first, and for [`Spi16`], which sends each
+/// word as a single 16-bit frame.
+pub trait WriteWord {
+    /// Communication error
+    type Error;
+
+    /// Send a 16-bit word to the device in one transaction
+    fn write_word(&mut self, word: u16) -> Result<(), Self::Error>;
+}
+
+/// SPI device using 16-bit frames
+///
+/// Wrap an `SpiDevice<u16>` with this to send each word to the device
+/// as a single frame.
+///
+/// ```
+/// use ad983x::{Ad983x, FrequencyRegister, Spi16};
+/// use embedded_hal::spi::SpiDevice;
+///
+/// fn configure<DEV: SpiDevice<u16>>(spi: DEV) {
+///     let mut dds = Ad983x::new_ad9833(Spi16::new(spi));
+///     dds.reset().unwrap();
+///     dds.set_frequency(FrequencyRegister::F0, 4724).unwrap();
+///     dds.enable().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Spi16<DEV> {
+    spi: DEV,
+}
+
+impl<DEV> Spi16<DEV> {
+    /// Create a new instance that sends 16-bit frames through the SPI device.
+    pub fn new(spi: DEV) -> Self {
+        Spi16 { spi }
+    }
+
+    /// Destroy the instance and return the SPI device.
+    pub fn destroy(self) -> DEV {
+        self.spi
+    }
+}
+
+impl<DEV> WriteWord for DEV
+where
+    DEV: SpiDevice,
+{
+    type Error = DEV::Error;
+
+    fn write_word(&mut self, word: u16) -> Result<(), Self::Error> {
+        self.write(&word.to_be_bytes())
+    }
+}
+
+impl<DEV> WriteWord for Spi16<DEV>
+where
+    DEV: SpiDevice<u16>,
+{
+    type Error = DEV::Error;
+
+    fn write_word(&mut self, word: u16) -> Result<(), Self::Error> {
+        self.spi.write(&[word])
+    }
+}
@@ -1,6 +1,7 @@
-//! This is a platform agnostic Rust driver for the AD9833, AD9834, AD9837
-//! and AD9838 low-power programmable waveform generators / direct digital
-//! synthesizers (DDS) using the [`embedded-hal`] traits.
+//! This is a platform agnostic Rust driver for the AD9833, AD9834, AD9837,
+//! AD9838, AD9832 and AD9835 low-power programmable waveform generators /
+//! direct digital synthesizers (DDS) and the AD5930 and AD5932 frequency
+//! sweep generators using the [`embedded-hal`] traits.
 //!
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
 //!
@@ -13,7 +14,49 @@
 //! - Set the frequency registers MSBs/LSBs separately. See: [`set_frequency_msb()`].
 //! - Set the output waveform. See: [`set_output_waveform()`].
 //! - Power down/up device parts. See: [`set_powered_down()`].
+//! - Output a square wave on any device. See: [`set_square_wave_output()`].
 //! - Select control source on AD9834/AD9838. See: [`set_control_source()`].
+//! - Use any device through the common [`Dds`] trait.
+//! - Select the device variant at runtime. See: [`AnyAd983x`].
+//! - Query the device capabilities and limits. See: [`capabilities()`].
+//! - Set the frequency in Hz for a configured MCLK. See: [`set_frequency_hz()`].
+//! - Configure a hardware frequency sweep on AD5930/AD5932. See: [`set_sweep()`].
+//! - Test application code without hardware through a software emulator
+//!   (`emulator` feature). See the `emulator` module.
+//! - Render the output of an emulated device as samples (`emulator` feature).
+//!   See the `renderer` module.
+//! - Record timed driver calls on an emulated device to a WAV file
+//!   (`wav` feature). See the `wav` module.
+//! - Decode captured SPI traffic into commands (`decoder` feature).
+//!   See the `decoder` module and the `ad983x-decode` command-line tool.
+//! - Trace the driver operations and the words sent to the device through
+//!   `log` or `defmt` (features of the same name). The records of the
+//!   operations and control register changes use the debug level and the
+//!   records of the words use the trace level. With the `defmt` feature the
+//!   public types implement `defmt::Format`.
+//! - Recover from SPI communication errors. See: [`resync()`].
+//! - Retry writes after transient SPI communication errors.
+//!   See: [`set_retry_policy()`].
+//! - Write several control register fields at once. See: [`write_control_word()`].
+//! - Encode the operations into words or bytes for other transports.
+//!   See the `encoder` module.
+//! - Drive the device through three GPIO pins. See the `bitbang` module.
+//! - Send each word as a single 16-bit SPI frame. See: [`Spi16`].
+//! - Start several devices phase-aligned and broadcast words to all of them.
+//!   See the `group` module.
+//! - Generate two outputs at the same frequency 90° apart from a device pair.
+//!   See the `quadrature` module.
+//! - Address many devices by channel index, apply configuration tables and
+//!   run bulk operations with per-channel errors. See the `channels` module.
+//! - Use an `embedded-hal` 0.2 SPI peripheral and FSYNC pin (`eh02` feature).
+//!   See the `eh02` module.
+//! - Drive AD9833/AD9834/AD9837/AD9838 devices through an
+//!   `embedded-hal-async` SPI device (`async` feature, Rust 1.75+).
+//!   See the `asynch` module.
+//! - Run sweeps, FSK keying and tone sequences that wait asynchronously
+//!   between the steps (`async` feature). See the `asynch::modulation` module.
+//! - Report errors with the exceeded limit. See [`Error`]. `core::error::Error`
+//!   is implemented with the `core-error` feature, which requires Rust 1.81.
 //!
 //! [`enable()`]: struct.Ad983x.html#method.enable
 //! [`set_frequency()`]: struct.Ad983x.html#method.set_frequency
@@ -23,7 +66,14 @@
 //! [`set_frequency_msb()`]: struct.Ad983x.html#method.set_frequency_msb
 //! [`set_output_waveform()`]: struct.Ad983x.html#method.set_output_waveform
 //! [`set_powered_down()`]: struct.Ad983x.html#method.set_powered_down
+//! [`set_square_wave_output()`]: struct.Ad983x.html#method.set_square_wave_output
 //! [`set_control_source()`]: struct.Ad983x.html#method.set_control_source
+//! [`capabilities()`]: struct.Ad983x.html#method.capabilities
+//! [`set_frequency_hz()`]: struct.Ad983x.html#method.set_frequency_hz
+//! [`set_sweep()`]: struct.Ad983x.html#method.set_sweep
+//! [`resync()`]: struct.Ad983x.html#method.resync
+//! [`set_retry_policy()`]: struct.Ad983x.html#method.set_retry_policy
+//! [`write_control_word()`]: struct.Ad983x.html#method.write_control_word
 //!
 //! [Introductory blog post](https://blog.eldruin.com/ad983x-waveform-generator-dds-driver-in-rust/)
 //!
@@ -43,7 +93,22 @@
 //! compatible with DSP and microcontroller standards. The devices operate
 //! with a power supply from 2.3 V to 5.5 V.
 //!
+//! The AD9832 and AD9835 are numerically controlled oscillators with 32-bit
+//! frequency registers and four 12-bit phase registers that produce a sine
+//! wave output. They use a different serial protocol. The frequency and phase
+//! register selection can be controlled through the FSELECT, PSEL0 and PSEL1
+//! pins, optionally synchronized to MCLK.
+//!
+//! The AD5930 and AD5932 generate frequency sweeps on-chip. The sweep is
+//! defined by a 24-bit start frequency, a frequency increment, the number of
+//! increments and the time interval between increments. The sweep is
+//! started through the CTRL pin. A square wave is available on the MSBOUT pin.
+//!
 //! Datasheets:
+//! - [AD5930](https://www.analog.com/media/en/technical-documentation/data-sheets/AD5930.pdf)
+//! - [AD5932](https://www.analog.com/media/en/technical-documentation/data-sheets/AD5932.pdf)
+//! - [AD9832](https://www.analog.com/media/en/technical-documentation/data-sheets/AD9832.pdf)
+//! - [AD9835](https://www.analog.com/media/en/technical-documentation/data-sheets/AD9835.pdf)
 //! - [AD9833](https://www.analog.com/media/en/technical-documentation/data-sheets/ad9833.PDF)
 //! - [AD9834](https://www.analog.com/media/en/technical-documentation/data-sheets/AD9834.PDF)
 //! - [AD9837](https://www.analog.com/media/en/technical-documentation/data-sheets/AD9837.PDF)
@@ -55,7 +120,7 @@
 //! Article explaining DDS using an AD9833:
 //! - [All about direct digital synthesis](https://www.analog.com/en/analog-dialogue/articles/all-about-direct-digital-synthesis.html)
 //!
-//! ## SysfsPin / Software control source on AD9834/AD9838
+//! ## Hardware pin / Software control source on AD9834/AD9838
 //!
 //! AD9834/AD9838 devices offer the possibility to control several functions
 //! either through hardware pins or software settings. While hardware pin
@@ -82,12 +147,9 @@
 //!
 //! ```no_run
 //! use ad983x::{Ad983x, FrequencyRegister};
-//! use embedded_hal::spi::blocking::ExclusiveDevice;
-//! use linux_embedded_hal::{SysfsPin, Spidev};
+//! use linux_embedded_hal::SpidevDevice;
 //!
-//! let spi = Spidev::open("/dev/spidev0.0").unwrap();
-//! let chip_select = SysfsPin::new(25);
-//! let dev = ExclusiveDevice::new(spi, chip_select);
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
 //! let mut dds = Ad983x::new_ad9833(dev);
 //! dds.reset().unwrap(); // reset is necessary before operation
 //! dds.set_frequency(FrequencyRegister::F0, 4724).unwrap();
@@ -106,12 +168,9 @@
 //!
 //! ```no_run
 //! use ad983x::{Ad983x, FrequencyRegister};
-//! use embedded_hal::spi::blocking::ExclusiveDevice;
-//! use linux_embedded_hal::{SysfsPin, Spidev};
+//! use linux_embedded_hal::SpidevDevice;
 //!
-//! let spi = Spidev::open("/dev/spidev0.0").unwrap();
-//! let chip_select = SysfsPin::new(25);
-//! let dev = ExclusiveDevice::new(spi, chip_select);
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
 //! let mut dds = Ad983x::new_ad9833(dev);
 //! dds.reset().unwrap(); // reset is necessary before operation
 //! // A4 tone for a 25 MHz clock
@@ -131,12 +190,9 @@
 //!
 //! ```no_run
 //! use ad983x::{Ad983x, PhaseRegister};
-//! use embedded_hal::spi::blocking::ExclusiveDevice;
-//! use linux_embedded_hal::{SysfsPin, Spidev};
+//! use linux_embedded_hal::SpidevDevice;
 //!
-//! let spi = Spidev::open("/dev/spidev0.0").unwrap();
-//! let chip_select = SysfsPin::new(25);
-//! let dev = ExclusiveDevice::new(spi, chip_select);
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
 //! let mut dds = Ad983x::new_ad9833(dev);
 //! dds.reset().unwrap(); // reset is necessary before operation
 //! dds.set_phase(PhaseRegister::P1, 4724).unwrap();
@@ -147,12 +203,9 @@
 //!
 //! ```no_run
 //! use ad983x::{Ad983x, OutputWaveform};
-//! use embedded_hal::spi::blocking::ExclusiveDevice;
-//! use linux_embedded_hal::{SysfsPin, Spidev};
+//! use linux_embedded_hal::SpidevDevice;
 //!
-//! let spi = Spidev::open("/dev/spidev0.0").unwrap();
-//! let chip_select = SysfsPin::new(25);
-//! let dev = ExclusiveDevice::new(spi, chip_select);
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
 //! let mut dds = Ad983x::new_ad9833(dev);
 //! dds.reset().unwrap(); // reset is necessary before operation
 //! dds.set_output_waveform(OutputWaveform::Triangle).unwrap();
@@ -162,27 +215,128 @@
 //!
 //! ```no_run
 //! use ad983x::{Ad983x, PoweredDown};
-//! use embedded_hal::spi::blocking::ExclusiveDevice;
-//! use linux_embedded_hal::{SysfsPin, Spidev};
+//! use linux_embedded_hal::SpidevDevice;
 //!
-//! let spi = Spidev::open("/dev/spidev0.0").unwrap();
-//! let chip_select = SysfsPin::new(25);
-//! let dev = ExclusiveDevice::new(spi, chip_select);
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
 //! let mut dds = Ad983x::new_ad9833(dev);
 //! dds.reset().unwrap(); // reset is necessary before operation
 //! dds.set_powered_down(PoweredDown::Dac).unwrap();
 //! ```
 //!
+//! ### Output a square wave
+//!
+//! The square wave is output on the VOUT pin on AD9833/AD9837 devices and on
+//! the SIGN BIT OUT pin on AD9834/AD9838 devices.
+//!
+//! ```no_run
+//! use ad983x::{Ad983x, SquareWave, SquareWavePin};
+//! use linux_embedded_hal::SpidevDevice;
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! let mut dds = Ad983x::new_ad9834(dev);
+//! dds.reset().unwrap(); // reset is necessary before operation
+//! let pin = dds.set_square_wave_output(SquareWave::MsbOfDacDiv2).unwrap();
+//! assert_eq!(SquareWavePin::SignBitOut, pin);
+//! ```
+//!
+//! ### Use any device through the `Dds` trait
+//!
+//! ```no_run
+//! use ad983x::{Ad983x, Dds, Error, FrequencyRegister};
+//! use linux_embedded_hal::SpidevDevice;
+//!
+//! fn play_a4<E>(dds: &mut dyn Dds<Error = Error<E>>) -> Result<(), Error<E>> {
+//!     dds.reset()?;
+//!     dds.set_frequency(FrequencyRegister::F0, 4724)?;
+//!     dds.enable()
+//! }
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! let mut dds = Ad983x::new_ad9838(dev);
+//! play_a4(&mut dds).unwrap();
+//! ```
+//!
+//! ### Set the frequency in Hz
+//!
+//! The MCLK frequency is checked against the device limits.
+//!
+//! ```no_run
+//! use ad983x::{Ad983x, FrequencyRegister};
+//! use linux_embedded_hal::SpidevDevice;
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! let mut dds = Ad983x::new_ad9837(dev);
+//! dds.set_mclk_frequency(16_000_000).unwrap();
+//! dds.reset().unwrap(); // reset is necessary before operation
+//! dds.set_frequency_hz(FrequencyRegister::F0, 440.0).unwrap();
+//! dds.enable().unwrap();
+//! ```
+//!
+//! ### Select the device variant at runtime
+//!
+//! ```no_run
+//! use ad983x::{AnyAd983x, FrequencyRegister, Variant};
+//! use linux_embedded_hal::SpidevDevice;
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! // For example, as read from an EEPROM
+//! let variant = Variant::Ad9837;
+//! let mut dds = AnyAd983x::new(dev, variant);
+//! dds.reset().unwrap(); // reset is necessary before operation
+//! dds.set_frequency(FrequencyRegister::F0, 4724).unwrap();
+//! dds.enable().unwrap();
+//! ```
+//!
+//! ### Use an AD9835 device
+//!
+//! ```no_run
+//! use ad983x::{Ad983x, ControlSource, ExtendedPhaseRegister, FrequencyRegister};
+//! use linux_embedded_hal::SpidevDevice;
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! let mut dds = Ad983x::new_ad9835(dev);
+//! dds.reset().unwrap(); // reset is necessary before operation
+//! dds.set_control_source(ControlSource::Software).unwrap();
+//! dds.set_frequency(FrequencyRegister::F0, 0x051E_B852).unwrap();
+//! dds.set_phase(ExtendedPhaseRegister::P3, 1024).unwrap();
+//! dds.select_phase(ExtendedPhaseRegister::P3).unwrap();
+//! dds.enable().unwrap();
+//! // Given a 50 MHz clock, this now outputs a 1 MHz sine wave.
+//! ```
+//!
+//! ### Configure a frequency sweep on an AD5932 device
+//!
+//! ```no_run
+//! use ad983x::{
+//!     Ad983x, IncrementInterval, IncrementIntervalBase, IncrementIntervalMultiplier,
+//!     SweepConfig,
+//! };
+//! use linux_embedded_hal::SpidevDevice;
+//!
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
+//! let mut dds = Ad983x::new_ad5932(dev);
+//! dds.set_mclk_frequency(25_000_000).unwrap();
+//! let sweep = SweepConfig {
+//!     start_frequency: 671, // 1 kHz
+//!     frequency_increment: 671,
+//!     increments: 100,
+//!     increment_interval: IncrementInterval {
+//!         base: IncrementIntervalBase::OutputCycles,
+//!         multiplier: IncrementIntervalMultiplier::X1,
+//!         count: 10,
+//!     },
+//! };
+//! dds.set_sweep(&sweep).unwrap();
+//! // A rising edge on the CTRL pin now starts a sweep from 1 kHz to 101 kHz.
+//! ```
+//!
 //! ### Use hardware pins as control source
 //!
 //! ```no_run
 //! use ad983x::{Ad983x, ControlSource};
-//! use embedded_hal::spi::blocking::ExclusiveDevice;
-//! use linux_embedded_hal::{SysfsPin, Spidev};
+//! use linux_embedded_hal::SpidevDevice;
 //!
-//! let spi = Spidev::open("/dev/spidev0.0").unwrap();
-//! let chip_select = SysfsPin::new(25);
-//! let dev = ExclusiveDevice::new(spi, chip_select);
+//! let dev = SpidevDevice::open("/dev/spidev0.0").unwrap();
 //! let mut dds = Ad983x::new_ad9838(dev);
 //! dds.reset().unwrap(); // reset is necessary before operation
 //! dds.set_control_source(ControlSource::HardwarePins).unwrap();
@@ -193,20 +347,106 @@
 #![deny(unsafe_code, missing_docs)]
 #![no_std]
 
+#[cfg(any(feature = "emulator", feature = "decoder"))]
+extern crate alloc;
+#[cfg(feature = "wav")]
+extern crate std;
+
 use core::marker::PhantomData;
 use embedded_hal::spi::{Mode, MODE_2};
 
 /// All possible errors in this crate
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<E> {
     /// SPI communication error
     Spi(E),
-    /// Invalid argument provided
-    InvalidArgument,
+    /// The value does not fit in the register
+    ValueTooWide {
+        /// Number of bits available for the value
+        bits: u8,
+    },
+    /// The value is outside of the allowed range
+    OutOfRange {
+        /// Minimum allowed value
+        min: i32,
+        /// Maximum allowed value
+        max: i32,
+    },
+    /// The output waveform is not available on the device
+    UnsupportedWaveform(OutputWaveform),
+    /// The master clock (MCLK) frequency is zero or exceeds the device maximum
+    InvalidMclkFrequency {
+        /// Maximum MCLK frequency of the device in Hz
+        max_hz: u32,
+    },
+    /// No master clock (MCLK) frequency has been configured
+    MclkFrequencyNotSet,
+    /// The frequency is negative or exceeds half the MCLK frequency
+    FrequencyOutOfRange {
+        /// Maximum frequency in Hz for the configured MCLK frequency
+        max_hz: f64,
+    },
+    /// Operation not supported by the device
+    Unsupported,
+    /// There is no channel with the given index
+    InvalidChannel {
+        /// Index of the channel
+        index: usize,
+    },
+}
+
+/// Device variant
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Variant {
+    /// AD9833 device
+    Ad9833,
+    /// AD9834 device
+    Ad9834,
+    /// AD9837 device
+    Ad9837,
+    /// AD9838 device
+    Ad9838,
+    /// AD9832 device
+    Ad9832,
+    /// AD9835 device
+    Ad9835,
+    /// AD5930 device
+    Ad5930,
+    /// AD5932 device
+    Ad5932,
+}
+
+/// Device capabilities and limits
+///
+/// See [`Variant::capabilities()`](enum.Variant.html#method.capabilities).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Capabilities {
+    /// Maximum master clock (MCLK) frequency in Hz
+    pub max_mclk_hz: u32,
+    /// Whether the functions can be controlled through hardware pins
+    /// (FSELECT, PSELECT, RESET and SLEEP)
+    pub pin_control: bool,
+    /// Whether a triangle wave can be output on VOUT
+    pub triangle_output: bool,
+    /// Whether a square wave can be output on VOUT
+    /// (`OutputWaveform::SquareMsbOfDac` and `SquareMsbOfDacDiv2`)
+    pub square_output: bool,
+    /// Output pin where the square wave is available, if any
+    pub square_wave_pin: Option<SquareWavePin>,
+    /// Whether the comparator and the SIGN BIT OUT pin are available
+    pub sign_bit_output: bool,
+    /// DAC resolution in bits
+    pub dac_bits: u8,
+    /// Frequency register width in bits
+    pub frequency_bits: u8,
 }
 
 /// Frequency registers
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FrequencyRegister {
     /// Frequency register 0
     F0,
@@ -216,6 +456,7 @@ pub enum FrequencyRegister {
 
 /// Phase registers
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PhaseRegister {
     /// Phase register 0
     P0,
@@ -223,8 +464,26 @@ pub enum PhaseRegister {
     P1,
 }
 
+/// Phase registers of AD9832/AD9835 devices
+///
+/// These devices have four phase registers. A `PhaseRegister` can be used
+/// in their place as well.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ExtendedPhaseRegister {
+    /// Phase register 0
+    P0,
+    /// Phase register 1
+    P1,
+    /// Phase register 2
+    P2,
+    /// Phase register 3
+    P3,
+}
+
 /// Output waveform
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum OutputWaveform {
     /// Sinusoidal wave (default)
     Sinusoidal,
@@ -240,6 +499,7 @@ pub enum OutputWaveform {
 
 /// Sign bit output on AD9834/AD9838 devices
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SignBitOutput {
     /// Disabled (high impedance) (default)
     Disabled,
@@ -251,8 +511,31 @@ pub enum SignBitOutput {
     SquareMsbOfDacDiv2,
 }
 
+/// Square wave output
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SquareWave {
+    /// Square wave with its value matching the MSB of DAC data
+    MsbOfDac,
+    /// Square wave with its value matching the MSB of DAC data divided by 2
+    MsbOfDacDiv2,
+}
+
+/// Output pin where the square wave is available
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SquareWavePin {
+    /// VOUT pin (AD9833/AD9837)
+    Vout,
+    /// SIGN BIT OUT pin (AD9834/AD9838)
+    SignBitOut,
+    /// MSBOUT pin (AD5930/AD5932)
+    MsbOut,
+}
+
 /// Powered-down device configuration
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PoweredDown {
     /// All chip parts are enabled (default)
     Nothing,
@@ -267,8 +550,11 @@ pub enum PoweredDown {
 /// Hardware pin / software control source for the functions:
 /// frequency register selection, phase register selection,
 /// reset of internal registers, and DAC power-down.
-/// (Only available on AD9834 and AD9838 devices)
+/// (Only available on AD9834, AD9838, AD9832 and AD9835 devices.
+/// On AD9832/AD9835 devices this only applies to the frequency and
+/// phase register selection.)
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ControlSource {
     /// Functions are controlled only through software (default)
     Software,
@@ -276,6 +562,120 @@ pub enum ControlSource {
     HardwarePins,
 }
 
+/// Control register value of AD9833/AD9834/AD9837/AD9838 devices
+///
+/// All the bits are cleared in a new value. The bits are set through the
+/// builder methods:
+///
+/// ```
+/// use ad983x::{ControlWord, FrequencyRegister, PhaseRegister};
+///
+/// let word = ControlWord::new()
+///     .b28(true)
+///     .fselect(FrequencyRegister::F1)
+///     .pselect(PhaseRegister::P1);
+/// assert_eq!(0x2C00, word.bits());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlWord {
+    bits: u16,
+}
+
+/// Retry policy for SPI communication errors
+///
+/// Failed words are sent again. Words that only make sense together, like
+/// the LSB and MSB halves of a frequency register, are sent again as a whole
+/// after writing the control register again to bring the device back in sync.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    ///
+    /// Values of 0 and 1 disable the retries.
+    pub max_attempts: u8,
+    /// Function called before each retry, for example to wait for some time
+    pub delay: Option<fn()>,
+}
+
+/// Frequency sweep configuration of AD5930/AD5932 devices
+///
+/// The frequencies are register values. The output frequency is
+/// `value * f_MCLK / 2^24`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SweepConfig {
+    /// Start frequency (24-bit value)
+    pub start_frequency: u32,
+    /// Frequency increment (signed 24-bit value: -(2^23 - 1) to 2^23 - 1)
+    ///
+    /// A negative value sweeps down from the start frequency.
+    pub frequency_increment: i32,
+    /// Number of frequency increments in the sweep (2 to 4095)
+    pub increments: u16,
+    /// Time interval between the frequency increments
+    pub increment_interval: IncrementInterval,
+}
+
+/// Time interval between the frequency increments of a sweep
+///
+/// The interval is `count * multiplier` periods of `base`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IncrementInterval {
+    /// Time base of the interval
+    pub base: IncrementIntervalBase,
+    /// Multiplier of the count
+    pub multiplier: IncrementIntervalMultiplier,
+    /// Number of periods (2 to 2047)
+    pub count: u16,
+}
+
+/// Time base of the increment interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IncrementIntervalBase {
+    /// Periods of the output waveform
+    OutputCycles,
+    /// Periods of the master clock (MCLK)
+    MclkPeriods,
+}
+
+/// Multiplier of the increment interval count
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IncrementIntervalMultiplier {
+    /// x1
+    X1,
+    /// x5
+    X5,
+    /// x100
+    X100,
+    /// x500
+    X500,
+}
+
+/// Trigger of the frequency increments of a sweep
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IncrementTrigger {
+    /// The frequency is incremented automatically after each increment interval (default)
+    Internal,
+    /// The frequency is incremented on each rising edge of the CTRL pin
+    External,
+}
+
+/// SYNCOUT pin configuration of AD5930/AD5932 devices
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SyncOutput {
+    /// Disabled (high impedance) (default)
+    Disabled,
+    /// Pulse at each frequency increment
+    EachIncrement,
+    /// Pulse at the end of the sweep
+    EndOfSweep,
+}
+
 /// SPI mode (CPOL = 1, CPHA = 0)
 pub const MODE: Mode = MODE_2;
 
@@ -283,9 +683,17 @@ pub const MODE: Mode = MODE_2;
 #[doc(hidden)]
 pub mod marker {
     /// AD9833/AD9837 device
+    #[derive(Debug)]
     pub enum Ad9833Ad9837 {}
     /// AD9834/AD9838 device
+    #[derive(Debug)]
     pub enum Ad9834Ad9838 {}
+    /// AD9832/AD9835 device
+    #[derive(Debug)]
+    pub enum Ad9832Ad9835 {}
+    /// AD5930/AD5932 device
+    #[derive(Debug)]
+    pub enum Ad5930Ad5932 {}
 }
 
 struct BitFlags;
@@ -300,17 +708,60 @@ struct Config {
 pub struct Ad983x<DEV, IC> {
     spi: DEV,
     control: Config,
+    variant: Variant,
+    mclk_hz: Option<u32>,
+    desynchronized: bool,
+    retry_policy: RetryPolicy,
+    retries: u32,
     _ic: PhantomData<IC>,
 }
 
+#[macro_use]
+mod trace;
+mod ad5930_ad5932;
+mod ad9832_ad9835;
 mod ad9833_ad9837;
 mod ad9834_ad9838;
+mod any;
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod bitbang;
+mod capabilities;
+pub mod channels;
 mod common;
+mod control_word;
+mod dds;
+#[cfg(feature = "decoder")]
+pub mod decoder;
+#[cfg(feature = "eh02")]
+pub mod eh02;
+#[cfg(feature = "emulator")]
+pub mod emulator;
+pub mod encoder;
+mod error;
+pub mod group;
+mod interface;
+pub mod quadrature;
+#[cfg(feature = "emulator")]
+pub mod renderer;
+#[cfg(feature = "wav")]
+pub mod wav;
+pub use crate::any::AnyAd983x;
+pub use crate::dds::Dds;
+pub use crate::interface::{Spi16, WriteWord};
 
 mod private {
     use super::marker;
     pub trait Sealed {}
 
+    /// Devices using the AD9833/AD9834 serial interface
+    pub trait Ad983xFamily: Sealed {}
+
     impl Sealed for marker::Ad9833Ad9837 {}
     impl Sealed for marker::Ad9834Ad9838 {}
+    impl Sealed for marker::Ad9832Ad9835 {}
+    impl Sealed for marker::Ad5930Ad5932 {}
+
+    impl Ad983xFamily for marker::Ad9833Ad9837 {}
+    impl Ad983xFamily for marker::Ad9834Ad9838 {}
 }
@@ -0,0 +1,194 @@
+//! Quadrature (I/Q) generator from a device pair
+//!
+//! A [`QuadraturePair`] drives two AD9833/AD9834/AD9837/AD9838 devices
+//! sharing the same MCLK so that they output the same frequency 90° apart.
+//! The I device uses a phase offset of 0 and the Q device a phase offset
+//! of 1024 (90°) plus an optional trim to correct the skew of the board.
+//!
+//! Both devices are started in sync through a broadcast interface that
+//! asserts the FSYNC line of both devices at the same time. See the
+//! [`group`](crate::group) module. Frequency changes are broadcast as well,
+//! so that both devices switch on the same MCLK edge and keep their phase
+//! relation.
+//!
+//! ```
+//! # use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+//! # struct Spi;
+//! # impl ErrorType for Spi { type Error = core::convert::Infallible; }
+//! # impl SpiDevice for Spi {
+//! #     fn transaction(&mut self, _: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let (spi_i, spi_q, broadcast) = (Spi, Spi, Spi);
+//! use ad983x::{quadrature::QuadraturePair, Ad983x};
+//!
+//! let i = Ad983x::new_ad9833(spi_i);
+//! let q = Ad983x::new_ad9833(spi_q);
+//! let mut pair = QuadraturePair::new(i, q, broadcast);
+//! pair.start(4724).unwrap();
+//! // Correct a measured skew of -0.5°
+//! pair.set_phase_trim(6).unwrap();
+//! pair.set_frequency(9448).unwrap();
+//! ```
+
+use crate::{
+    common::check_value_fits,
+    encoder,
+    group::{GroupError, SyncGroup},
+    private, Ad983x, BitFlags, FrequencyRegister, PhaseRegister, WriteWord,
+};
+
+/// Phase offset of the Q device (90°)
+const QUADRATURE_PHASE: i32 = 1024;
+
+/// Pair of devices outputting the same frequency 90° apart
+#[derive(Debug)]
+pub struct QuadraturePair<DEV, IC, B> {
+    devices: [Ad983x<DEV, IC>; 2],
+    broadcast: B,
+    phase_trim: i16,
+}
+
+impl<DEV, IC, B> QuadraturePair<DEV, IC, B> {
+    /// Create a new pair from the I and Q devices and the broadcast interface.
+    pub fn new(i: Ad983x<DEV, IC>, q: Ad983x<DEV, IC>, broadcast: B) -> Self {
+        QuadraturePair {
+            devices: [i, q],
+            broadcast,
+            phase_trim: 0,
+        }
+    }
+
+    /// Get the phase trim of the Q device
+    pub fn phase_trim(&self) -> i16 {
+        self.phase_trim
+    }
+
+    /// Get the I device
+    pub fn i(&mut self) -> &mut Ad983x<DEV, IC> {
+        &mut self.devices[0]
+    }
+
+    /// Get the Q device
+    pub fn q(&mut self) -> &mut Ad983x<DEV, IC> {
+        &mut self.devices[1]
+    }
+
+    /// Destroy the pair, return the I and Q devices and the broadcast interface.
+    pub fn destroy(self) -> (Ad983x<DEV, IC>, Ad983x<DEV, IC>, B) {
+        let [i, q] = self.devices;
+        (i, q, self.broadcast)
+    }
+
+    /// Phase register value of the Q device
+    fn q_phase(&self) -> u16 {
+        (QUADRATURE_PHASE + i32::from(self.phase_trim)).rem_euclid(1 << 12) as u16
+    }
+}
+
+impl<DEV, IC, B, E> QuadraturePair<DEV, IC, B>
+where
+    DEV: WriteWord<Error = E>,
+    IC: private::Ad983xFamily,
+    B: WriteWord<Error = E>,
+{
+    /// Load the frequency and the phase offsets while both devices are held
+    /// in reset and start them in sync.
+    ///
+    /// The F0 and P0 registers are used.
+    /// Returns `GroupError::Device` with `Error::ValueTooWide` for the I device
+    /// before writing anything if providing a value that does not fit in 28 bits.
+    pub fn start(&mut self, frequency: u32) -> Result<(), GroupError<E>> {
+        check_value_fits(frequency, 28).map_err(|error| GroupError::Device { index: 0, error })?;
+        trace_op!("quadrature start {:#x}", frequency);
+        let phases = [0, self.q_phase()];
+        let mut group = SyncGroup::new(&mut self.devices);
+        group.load(|index, dds| {
+            let control = dds
+                .control
+                .with_frequency_select(FrequencyRegister::F0)
+                .with_phase_select(PhaseRegister::P0);
+            dds.write_control_if_different(control)?;
+            dds.set_frequency(FrequencyRegister::F0, frequency)?;
+            dds.set_phase(PhaseRegister::P0, phases[index])
+        })?;
+        group.start(&mut self.broadcast)
+    }
+
+    /// Set the frequency of both devices as a 28-bit word
+    ///
+    /// The words are broadcast so that both devices switch at the same time.
+    /// The control register is broadcast first if the 28-bit mode is not used
+    /// or the state of the devices is unknown.
+    /// Returns `GroupError::Device` with `Error::ValueTooWide` for the I device
+    /// if providing a value that does not fit in 28 bits.
+    /// Returns `GroupError::ControlMismatch` before writing anything if the
+    /// control register settings of the devices differ apart from the 28-bit mode.
+    pub fn set_frequency(&mut self, value: u32) -> Result<(), GroupError<E>> {
+        check_value_fits(value, 28).map_err(|error| GroupError::Device { index: 0, error })?;
+        trace_op!("quadrature set_frequency {:#x}", value);
+        // Write the frequency register that is in use
+        let register = if self.devices[0].control.bits & BitFlags::FSELECT != 0 {
+            FrequencyRegister::F1
+        } else {
+            FrequencyRegister::F0
+        };
+        let control = self.devices[0].control.with_high(BitFlags::B28);
+        let mismatch = self
+            .devices
+            .iter()
+            .position(|dds| dds.control.with_high(BitFlags::B28) != control);
+        if let Some(index) = mismatch {
+            return Err(GroupError::ControlMismatch { index });
+        }
+        let mut group = SyncGroup::new(&mut self.devices);
+        let in_sync = group
+            .devices()
+            .iter()
+            .all(|dds| dds.control == control && !dds.is_desynchronized());
+        if !in_sync {
+            group.broadcast(
+                &mut self.broadcast,
+                &[encoder::control_payload(control.bits)],
+            )?;
+        }
+        group.broadcast(
+            &mut self.broadcast,
+            &encoder::frequency_words(register, value),
+        )
+    }
+
+    /// Set the frequency of both devices in Hz
+    ///
+    /// The value written into the frequency registers is calculated from the
+    /// MCLK frequency configured on the I device.
+    /// Returns `GroupError::Device` for the I device with `Error::MclkFrequencyNotSet`
+    /// if no MCLK frequency has been configured or `Error::FrequencyOutOfRange` if the
+    /// frequency is negative or exceeds half the MCLK frequency.
+    pub fn set_frequency_hz(&mut self, frequency_hz: f64) -> Result<(), GroupError<E>> {
+        let value = self.devices[0]
+            .frequency_register_value(frequency_hz)
+            .map_err(|error| GroupError::Device { index: 0, error })?;
+        self.set_frequency(value)
+    }
+
+    /// Set the phase trim of the Q device to correct the skew of the board.
+    ///
+    /// The trim is added to the 1024 (90°) phase offset of the Q device in
+    /// steps of 360°/4096. The phase register of the Q device is written
+    /// right away.
+    pub fn set_phase_trim(&mut self, trim: i16) -> Result<(), GroupError<E>> {
+        trace_op!("quadrature set_phase_trim {}", trim);
+        self.phase_trim = trim;
+        let phase = self.q_phase();
+        let register = if self.devices[1].control.bits & BitFlags::PSELECT != 0 {
+            PhaseRegister::P1
+        } else {
+            PhaseRegister::P0
+        };
+        self.devices[1]
+            .set_phase(register, phase)
+            .map_err(|error| GroupError::Device { index: 1, error })
+    }
+}
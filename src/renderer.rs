@@ -0,0 +1,196 @@
+//! Waveform sample renderer based on the DDS signal chain
+//!
+//! The renderer turns the register state of an [`Emulator`] into output
+//! samples at a chosen sample rate. It models:
+//! - The 28-bit phase accumulator, which advances by the selected frequency
+//!   register value on each MCLK cycle.
+//! - The addition of the selected phase register and the truncation of the
+//!   phase to 12 bits.
+//! - The sine ROM and the 10-bit DAC quantization.
+//! - The triangle mode, where the sine ROM is bypassed.
+//! - The MSB and MSB/2 square outputs.
+//!
+//! The accumulator state is kept between calls so the register state can be
+//! changed in between, for example to check FSK switching or phase steps.
+//!
+//! This module is available with the `emulator` feature.
+//!
+//! ```
+//! use ad983x::{emulator::Emulator, renderer::Renderer, Ad983x, FrequencyRegister, Variant};
+//!
+//! let emulator = Emulator::new(Variant::Ad9833).unwrap();
+//! let mut dds = Ad983x::new_ad9833(emulator.clone());
+//! dds.reset().unwrap();
+//! dds.set_frequency(FrequencyRegister::F0, 1 << 24).unwrap();
+//! dds.set_frequency(FrequencyRegister::F1, 1 << 25).unwrap();
+//! dds.enable().unwrap();
+//!
+//! // 1 MHz MCLK, 1 MHz sample rate: the output frequency is 62.5 kHz.
+//! let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+//! let mut samples = [0.0; 64];
+//! renderer.render(&emulator, &mut samples[..32]);
+//! // FSK: switch to 125 kHz
+//! dds.select_frequency(FrequencyRegister::F1).unwrap();
+//! renderer.render(&emulator, &mut samples[32..]);
+//! ```
+
+use crate::{emulator::Emulator, OutputWaveform, PoweredDown, SignBitOutput};
+
+const ACCUMULATOR_MASK: u32 = (1 << 28) - 1;
+const DAC_MAX: u16 = (1 << 10) - 1;
+
+/// Output samples of all the device output pins
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// VOUT pin level (-1.0 to 1.0 of the full-scale range)
+    ///
+    /// This is 0.0 while the DAC is powered down.
+    pub vout: f32,
+    /// SIGN BIT OUT pin level (AD9834/AD9838 devices)
+    ///
+    /// This is `None` while the pin is disabled (high impedance).
+    /// The comparator output is modeled as the MSB of the DAC data,
+    /// i.e. as if VOUT was filtered ideally.
+    pub sign_bit_out: Option<bool>,
+}
+
+/// Renderer of the analog output of an emulated device
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    mclk_cycles_per_sample: f64,
+    mclk_cycles: f64,
+    accumulator: u32,
+    msb_div2: bool,
+}
+
+impl Renderer {
+    /// Create a new renderer for the given master clock (MCLK) frequency and
+    /// sample rate in Hz.
+    ///
+    /// The phase accumulator starts at zero.
+    /// Returns `None` if the MCLK frequency or the sample rate is zero.
+    pub fn new(mclk_hz: u32, sample_rate_hz: u32) -> Option<Self> {
+        if mclk_hz == 0 || sample_rate_hz == 0 {
+            return None;
+        }
+        Some(Renderer {
+            mclk_cycles_per_sample: f64::from(mclk_hz) / f64::from(sample_rate_hz),
+            mclk_cycles: 0.0,
+            accumulator: 0,
+            msb_div2: false,
+        })
+    }
+
+    /// Get the current 28-bit phase accumulator value
+    pub fn accumulator(&self) -> u32 {
+        self.accumulator
+    }
+
+    /// Render VOUT samples for the current register state
+    pub fn render(&mut self, emulator: &Emulator, samples: &mut [f32]) {
+        for sample in samples {
+            *sample = self.next_sample(emulator).vout;
+        }
+    }
+
+    /// Render the next sample of all output pins for the current register state
+    pub fn next_sample(&mut self, emulator: &Emulator) -> Sample {
+        self.advance(emulator);
+        let phase = self.truncated_phase(emulator);
+        let dac = match emulator.output_waveform() {
+            OutputWaveform::Triangle => triangle(phase),
+            _ => sine(phase),
+        };
+        // The sine ROM uses the phase MSB as sign bit
+        let msb = match emulator.output_waveform() {
+            OutputWaveform::Triangle => dac & (1 << 9) != 0,
+            _ => phase & (1 << 11) == 0,
+        };
+        let vout = match emulator.output_waveform() {
+            OutputWaveform::Sinusoidal | OutputWaveform::Triangle => dac_level(dac),
+            OutputWaveform::SquareMsbOfDac => digital_level(msb),
+            OutputWaveform::SquareMsbOfDacDiv2 => digital_level(self.msb_div2),
+        };
+        let dac_off = matches!(
+            emulator.powered_down(),
+            PoweredDown::Dac | PoweredDown::DacAndInternalClock
+        );
+        let vout_is_dac = matches!(
+            emulator.output_waveform(),
+            OutputWaveform::Sinusoidal | OutputWaveform::Triangle
+        );
+        let sign_bit_out = match emulator.sign_bit_output() {
+            SignBitOutput::Disabled => None,
+            SignBitOutput::Comparator | SignBitOutput::SquareMsbOfDac => Some(msb),
+            SignBitOutput::SquareMsbOfDacDiv2 => Some(self.msb_div2),
+        };
+        Sample {
+            vout: if dac_off && vout_is_dac { 0.0 } else { vout },
+            sign_bit_out,
+        }
+    }
+
+    /// Advance the phase accumulator to the next sample instant.
+    fn advance(&mut self, emulator: &Emulator) {
+        self.mclk_cycles += self.mclk_cycles_per_sample;
+        let cycles = self.mclk_cycles as u64;
+        self.mclk_cycles -= cycles as f64;
+        if emulator.is_reset() {
+            self.accumulator = 0;
+            self.msb_div2 = false;
+            return;
+        }
+        let clock_off = matches!(
+            emulator.powered_down(),
+            PoweredDown::InternalClock | PoweredDown::DacAndInternalClock
+        );
+        if clock_off {
+            return;
+        }
+        let frequency = u64::from(emulator.frequency(emulator.selected_frequency()));
+        let accumulator = u64::from(self.accumulator) + cycles * frequency;
+        // The MSB/2 output toggles on each rising edge of the MSB,
+        // which happens once per accumulator overflow.
+        let overflows = accumulator >> 28;
+        if overflows & 1 != 0 {
+            self.msb_div2 = !self.msb_div2;
+        }
+        self.accumulator = (accumulator as u32) & ACCUMULATOR_MASK;
+    }
+
+    /// Add the phase register and truncate to 12 bits.
+    fn truncated_phase(&self, emulator: &Emulator) -> u16 {
+        let phase = emulator.phase(emulator.selected_phase());
+        (((self.accumulator >> 16) as u16) + phase) & 0xFFF
+    }
+}
+
+/// Sine ROM followed by the 10-bit DAC quantization
+fn sine(phase: u16) -> u16 {
+    let angle = 2.0 * core::f64::consts::PI * f64::from(phase) / 4096.0;
+    let level = (libm::sin(angle) + 1.0) / 2.0;
+    libm::round(level * f64::from(DAC_MAX)) as u16
+}
+
+/// The sine ROM is bypassed and the 11 MSBs of the phase are folded
+/// into the 10-bit DAC range.
+fn triangle(phase: u16) -> u16 {
+    let phase = phase >> 1;
+    if phase <= DAC_MAX {
+        phase
+    } else {
+        2 * DAC_MAX + 1 - phase
+    }
+}
+
+fn dac_level(code: u16) -> f32 {
+    (f64::from(code) / f64::from(DAC_MAX) * 2.0 - 1.0) as f32
+}
+
+fn digital_level(high: bool) -> f32 {
+    if high {
+        1.0
+    } else {
+        -1.0
+    }
+}
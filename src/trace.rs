@@ -0,0 +1,27 @@
+//! Tracing of the driver operations and of the words sent to the device.
+//!
+//! The records are emitted through `log` and/or `defmt` if the corresponding
+//! features are enabled. Otherwise the macros expand to nothing and their
+//! arguments are not evaluated.
+//!
+//! The format strings must be understood by both `core::fmt` and `defmt`.
+
+/// Emit a record for a logical operation (debug level).
+macro_rules! trace_op {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        ::log::debug!(target: "ad983x", $($arg)+);
+        #[cfg(feature = "defmt")]
+        ::defmt::debug!($($arg)+);
+    };
+}
+
+/// Emit a record for a raw word sent to the device (trace level).
+macro_rules! trace_word {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        ::log::trace!(target: "ad983x", $($arg)+);
+        #[cfg(feature = "defmt")]
+        ::defmt::trace!($($arg)+);
+    };
+}
@@ -0,0 +1,151 @@
+//! Recording of the emulated output to WAV files
+//!
+//! A [`WavRecorder`] renders the output of an [`Emulator`] while driver
+//! calls are run at given times. The VOUT samples can then be written as a
+//! mono 16-bit PCM WAV file.
+//!
+//! This module is available with the `wav` feature, which requires `std`.
+//!
+//! ```no_run
+//! use ad983x::{emulator::Emulator, wav::WavRecorder, Ad983x, FrequencyRegister, Variant};
+//! use std::time::Duration;
+//!
+//! let emulator = Emulator::new(Variant::Ad9833).unwrap();
+//! let mut dds = Ad983x::new_ad9833(emulator.clone());
+//! let mut recorder = WavRecorder::new(emulator, 25_000_000, 48_000).unwrap();
+//! dds.set_mclk_frequency(25_000_000).unwrap();
+//! dds.reset().unwrap();
+//! dds.set_frequency_hz(FrequencyRegister::F0, 1200.0).unwrap();
+//! dds.set_frequency_hz(FrequencyRegister::F1, 2200.0).unwrap();
+//! dds.enable().unwrap();
+//! // AFSK at 1200 baud
+//! let bit_time = Duration::from_nanos(1_000_000_000 / 1200);
+//! for (i, bit) in [true, false, true, true, false].iter().enumerate() {
+//!     let register = if *bit {
+//!         FrequencyRegister::F0
+//!     } else {
+//!         FrequencyRegister::F1
+//!     };
+//!     recorder
+//!         .at(bit_time * i as u32, || dds.select_frequency(register))
+//!         .unwrap();
+//! }
+//! recorder.render_until(bit_time * 5);
+//! recorder.save("afsk.wav").unwrap();
+//! ```
+
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+    vec::Vec,
+};
+
+use crate::{emulator::Emulator, renderer::Renderer};
+
+/// Recorder of the output of an emulated device
+#[derive(Debug, Clone)]
+pub struct WavRecorder {
+    emulator: Emulator,
+    renderer: Renderer,
+    sample_rate_hz: u32,
+    samples: Vec<i16>,
+}
+
+impl WavRecorder {
+    /// Create a new recorder for an emulated device.
+    ///
+    /// The master clock (MCLK) frequency and the sample rate are given in Hz.
+    /// The recording starts at time zero.
+    /// Returns `None` if the MCLK frequency or the sample rate is zero.
+    pub fn new(emulator: Emulator, mclk_hz: u32, sample_rate_hz: u32) -> Option<Self> {
+        Some(WavRecorder {
+            emulator,
+            renderer: Renderer::new(mclk_hz, sample_rate_hz)?,
+            sample_rate_hz,
+            samples: Vec::new(),
+        })
+    }
+
+    /// Get the current recording time
+    pub fn time(&self) -> Duration {
+        let samples = self.samples.len() as u64;
+        let rate = u64::from(self.sample_rate_hz);
+        Duration::from_secs(samples / rate)
+            + Duration::from_nanos((samples % rate) * 1_000_000_000 / rate)
+    }
+
+    /// Get the recorded samples
+    pub fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+
+    /// Render the output up to the given time with the current register state.
+    ///
+    /// Nothing is rendered if the time is not after the current recording time.
+    pub fn render_until(&mut self, time: Duration) {
+        let target = time.as_nanos() * u128::from(self.sample_rate_hz) / 1_000_000_000;
+        while (self.samples.len() as u128) < target {
+            let vout = self.renderer.next_sample(&self.emulator).vout;
+            self.samples
+                .push((vout * f32::from(i16::MAX)).round() as i16);
+        }
+    }
+
+    /// Run a command at the given time.
+    ///
+    /// The output is rendered up to the given time before running the command.
+    /// Commands given a time in the past are run at the current recording time.
+    pub fn at<F, R>(&mut self, time: Duration, command: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.render_until(time);
+        command()
+    }
+
+    /// Write the recorded samples as a mono 16-bit PCM WAV file.
+    ///
+    /// Returns an error of kind `InvalidInput` if the recording is too long
+    /// or the sample rate too high to be described in the WAV header.
+    pub fn write_wav<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let too_large = |what| io::Error::new(io::ErrorKind::InvalidInput, what);
+        let data_len = self
+            .samples
+            .len()
+            .checked_mul(2)
+            .and_then(|len| u32::try_from(len).ok())
+            .ok_or_else(|| too_large("recording too long for a WAV file"))?;
+        let riff_len = data_len
+            .checked_add(36)
+            .ok_or_else(|| too_large("recording too long for a WAV file"))?;
+        let byte_rate = self
+            .sample_rate_hz
+            .checked_mul(2)
+            .ok_or_else(|| too_large("sample rate too high for a WAV file"))?;
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&riff_len.to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?; // fmt chunk size
+        writer.write_all(&1u16.to_le_bytes())?; // PCM
+        writer.write_all(&1u16.to_le_bytes())?; // mono
+        writer.write_all(&self.sample_rate_hz.to_le_bytes())?;
+        writer.write_all(&byte_rate.to_le_bytes())?;
+        writer.write_all(&2u16.to_le_bytes())?; // block align
+        writer.write_all(&16u16.to_le_bytes())?; // bits per sample
+        writer.write_all(b"data")?;
+        writer.write_all(&data_len.to_le_bytes())?;
+        for sample in &self.samples {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Save the recorded samples to a mono 16-bit PCM WAV file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_wav(BufWriter::new(File::create(path)?))
+    }
+}
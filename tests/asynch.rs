@@ -0,0 +1,337 @@
+use ad983x::{
+    asynch::{
+        modulation::{Fsk, Modulator, Outcome, StopSignal, Sweep, Tone},
+        Ad983xAsync,
+    },
+    ControlSource, Error, FrequencyRegister as FreqReg, OutputWaveform, PhaseRegister as PhaseReg,
+    PoweredDown, SquareWave, SquareWavePin,
+};
+use embedded_hal_async::{
+    delay::DelayNs,
+    spi::{ErrorKind, ErrorType, Operation, SpiDevice},
+};
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct SpiError;
+
+impl embedded_hal_async::spi::Error for SpiError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// SPI device that records the bytes written in each transaction
+#[derive(Debug, Default)]
+struct Recorder {
+    transactions: Vec<Vec<u8>>,
+    /// Indices of the transactions that fail
+    failing: Vec<usize>,
+    count: usize,
+    /// Return `Poll::Pending` once in each transaction
+    yielding: bool,
+}
+
+/// Future that is pending the first time it is polled
+struct YieldOnce {
+    yielded: bool,
+}
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+impl ErrorType for Recorder {
+    type Error = SpiError;
+}
+
+impl SpiDevice for Recorder {
+    async fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), SpiError> {
+        if self.yielding {
+            YieldOnce { yielded: false }.await;
+        }
+        let index = self.count;
+        self.count += 1;
+        if self.failing.contains(&index) {
+            return Err(SpiError);
+        }
+        let mut bytes = Vec::new();
+        for operation in operations {
+            if let Operation::Write(words) = operation {
+                bytes.extend_from_slice(words);
+            }
+        }
+        self.transactions.push(bytes);
+        Ok(())
+    }
+}
+
+fn words(dev: Ad983xAsync<Recorder, impl Sized>) -> Vec<u16> {
+    dev.destroy()
+        .transactions
+        .iter()
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        .collect()
+}
+
+#[test]
+fn can_configure_ad9833() {
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    block_on(async {
+        dev.reset().await.unwrap();
+        dev.set_frequency(FreqReg::F1, 0x9AB_CDEF).await.unwrap();
+        dev.select_frequency(FreqReg::F1).await.unwrap();
+        dev.set_phase(PhaseReg::P1, 0x123).await.unwrap();
+        dev.select_phase(PhaseReg::P1).await.unwrap();
+        dev.set_output_waveform(OutputWaveform::Triangle)
+            .await
+            .unwrap();
+        dev.set_powered_down(PoweredDown::Dac).await.unwrap();
+        dev.enable().await.unwrap();
+    });
+    assert_eq!(
+        vec![0x0100, 0x2100, 0x8DEF, 0xA6AF, 0x2900, 0xE123, 0x2D00, 0x2D02, 0x2D42, 0x2C42],
+        words(dev)
+    );
+}
+
+#[test]
+fn can_set_frequency_halves() {
+    let mut dev = Ad983xAsync::new_ad9837(Recorder::default());
+    block_on(async {
+        dev.set_frequency_msb(FreqReg::F0, 0x3FFF).await.unwrap();
+        dev.set_frequency_lsb(FreqReg::F0, 1).await.unwrap();
+    });
+    assert_eq!(vec![0x1100, 0x7FFF, 0x0100, 0x4001], words(dev));
+}
+
+#[test]
+fn can_set_control_source_ad9834() {
+    let mut dev = Ad983xAsync::new_ad9834(Recorder::default());
+    block_on(async {
+        dev.set_control_source(ControlSource::HardwarePins)
+            .await
+            .unwrap();
+        let pin = dev
+            .set_square_wave_output(SquareWave::MsbOfDac)
+            .await
+            .unwrap();
+        assert_eq!(SquareWavePin::SignBitOut, pin);
+    });
+    assert_eq!(vec![0x0300, 0x0328], words(dev));
+}
+
+#[test]
+fn cannot_set_square_waveform_ad9838() {
+    let mut dev = Ad983xAsync::new_ad9838(Recorder::default());
+    let result = block_on(dev.set_output_waveform(OutputWaveform::SquareMsbOfDac));
+    match result {
+        Err(Error::UnsupportedWaveform(OutputWaveform::SquareMsbOfDac)) => (),
+        _ => panic!("Should return Error::UnsupportedWaveform"),
+    }
+}
+
+#[test]
+fn cannot_set_too_wide_phase() {
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    match block_on(dev.set_phase(PhaseReg::P0, 1 << 12)) {
+        Err(Error::ValueTooWide { bits: 12 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
+}
+
+#[test]
+fn resyncs_after_spi_error() {
+    let spi = Recorder {
+        failing: vec![1],
+        ..Recorder::default()
+    };
+    let mut dev = Ad983xAsync::new_ad9833(spi);
+    block_on(async {
+        dev.reset().await.unwrap();
+        match dev.enable().await {
+            Err(Error::Spi(SpiError)) => (),
+            _ => panic!("Should return Error::Spi"),
+        }
+        assert!(dev.is_desynchronized());
+        dev.set_phase(PhaseReg::P0, 1).await.unwrap();
+        assert!(!dev.is_desynchronized());
+    });
+    assert_eq!(vec![0x0100, 0x0100, 0xC001], words(dev));
+}
+
+/// Delay that records the waits and optionally sets a stop signal
+#[derive(Default)]
+struct Delay<'a> {
+    waits_us: Vec<u32>,
+    stop_after: Option<(usize, &'a StopSignal)>,
+}
+
+impl DelayNs for Delay<'_> {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.waits_us.push(ns / 1000);
+        if let Some((count, stop)) = self.stop_after {
+            if self.waits_us.len() == count {
+                stop.stop();
+            }
+        }
+    }
+}
+
+#[test]
+fn can_sweep_through_alternate_registers() {
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    let mut modulator = Modulator::new(Delay::default());
+    let sweep = Sweep {
+        start_frequency: 100,
+        frequency_increment: 50,
+        steps: 3,
+        step_us: 10,
+    };
+    let outcome = block_on(modulator.sweep(&mut dev, &sweep)).unwrap();
+    assert_eq!(Outcome::Completed, outcome);
+    assert_eq!(vec![10, 10, 10], modulator.destroy().waits_us);
+    assert_eq!(
+        vec![0x2100, 0x8064, 0x8000, 0x2900, 0x4096, 0x4000, 0x2100, 0x80C8, 0x8000, 0x2900],
+        words(dev)
+    );
+}
+
+#[test]
+fn cannot_sweep_out_of_range() {
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    let mut modulator = Modulator::new(Delay::default());
+    let sweep = Sweep {
+        start_frequency: 100,
+        frequency_increment: -50,
+        steps: 4,
+        step_us: 10,
+    };
+    match block_on(modulator.sweep(&mut dev, &sweep)) {
+        Err(Error::ValueTooWide { bits: 28 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
+    assert!(words(dev).is_empty());
+}
+
+#[test]
+fn can_key_fsk() {
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    let mut modulator = Modulator::new(Delay::default());
+    let fsk = Fsk {
+        space_frequency: 1,
+        mark_frequency: 2,
+        bit_us: 20,
+    };
+    let outcome = block_on(modulator.fsk(&mut dev, &fsk, [true, false, true])).unwrap();
+    assert_eq!(Outcome::Completed, outcome);
+    assert_eq!(vec![20, 20, 20], modulator.destroy().waits_us);
+    assert_eq!(
+        vec![0x0100, 0x2100, 0x4001, 0x4000, 0x8002, 0x8000, 0x2900, 0x2100, 0x2900, 0x2100],
+        words(dev)
+    );
+}
+
+#[test]
+fn stops_fsk_on_space_frequency() {
+    let stop = StopSignal::new();
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    let delay = Delay {
+        stop_after: Some((1, &stop)),
+        ..Delay::default()
+    };
+    let mut modulator = Modulator::new(delay).with_stop_signal(&stop);
+    let fsk = Fsk {
+        space_frequency: 1,
+        mark_frequency: 2,
+        bit_us: 20,
+    };
+    let outcome = block_on(modulator.fsk(&mut dev, &fsk, [true, true, true])).unwrap();
+    assert_eq!(Outcome::Stopped, outcome);
+    assert_eq!(
+        vec![0x0100, 0x2100, 0x4001, 0x4000, 0x8002, 0x8000, 0x2900, 0x2100],
+        words(dev)
+    );
+}
+
+#[test]
+fn can_stop_tones() {
+    let stop = StopSignal::new();
+    let mut dev = Ad983xAsync::new_ad9833(Recorder::default());
+    let delay = Delay {
+        stop_after: Some((2, &stop)),
+        ..Delay::default()
+    };
+    let mut modulator = Modulator::new(delay).with_stop_signal(&stop);
+    let tones = [Tone {
+        frequency: 3,
+        duration_us: 5,
+    }; 4];
+    let outcome = block_on(modulator.tones(&mut dev, &tones)).unwrap();
+    assert_eq!(Outcome::Stopped, outcome);
+    assert!(stop.is_stopped());
+    assert_eq!(vec![5, 5], modulator.destroy().waits_us);
+    assert_eq!(
+        vec![0x2100, 0x8003, 0x8000, 0x2900, 0x4003, 0x4000, 0x2100],
+        words(dev)
+    );
+}
+
+#[test]
+fn resyncs_after_dropping_tones() {
+    let spi = Recorder {
+        yielding: true,
+        ..Recorder::default()
+    };
+    let mut dev = Ad983xAsync::new_ad9833(spi);
+    let mut modulator = Modulator::new(Delay::default());
+    let tones = [Tone {
+        frequency: 3,
+        duration_us: 5,
+    }; 2];
+    {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(modulator.tones(&mut dev, &tones));
+        // Complete the first two words and drop the future while the third is pending
+        for _ in 0..3 {
+            assert!(future.as_mut().poll(&mut context).is_pending());
+        }
+    }
+    assert!(dev.is_desynchronized());
+    block_on(dev.set_phase(PhaseReg::P0, 1)).unwrap();
+    assert!(!dev.is_desynchronized());
+    assert_eq!(vec![0x2100, 0x8003, 0x2100, 0xC001], words(dev));
+}
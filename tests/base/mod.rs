@@ -1,5 +1,10 @@
-use ad983x::{marker, Ad983x};
-use embedded_hal_mock::spi::{Mock as SpiMock, Transaction as SpiTrans};
+use ad983x::{marker, Ad983x, AnyAd983x, Spi16, Variant};
+use embedded_hal::spi::{ErrorKind, ErrorType, Operation, SpiDevice};
+use embedded_hal_mock::eh1::spi::{Mock, Transaction};
+use std::convert::Infallible;
+
+pub type SpiMock = Mock<u8>;
+pub type SpiTrans = Transaction<u8>;
 
 pub struct BitFlags;
 impl BitFlags {
@@ -78,6 +83,165 @@ pub fn new_ad9838(transactions: &[SpiTrans]) -> Ad983x<SpiMock, marker::Ad9834Ad
     Ad983x::new_ad9838(SpiMock::new(wrapped.iter()))
 }
 
+pub fn new_ad9832(transactions: &[SpiTrans]) -> Ad983x<SpiMock, marker::Ad9832Ad9835> {
+    let wrapped: Vec<SpiTrans> = transactions
+        .iter()
+        .flat_map(|trans| {
+            [
+                SpiTrans::transaction_start(),
+                trans.clone(),
+                SpiTrans::transaction_end(),
+            ]
+        })
+        .collect();
+    Ad983x::new_ad9832(SpiMock::new(wrapped.iter()))
+}
+
+pub fn new_ad9835(transactions: &[SpiTrans]) -> Ad983x<SpiMock, marker::Ad9832Ad9835> {
+    let wrapped: Vec<SpiTrans> = transactions
+        .iter()
+        .flat_map(|trans| {
+            [
+                SpiTrans::transaction_start(),
+                trans.clone(),
+                SpiTrans::transaction_end(),
+            ]
+        })
+        .collect();
+    Ad983x::new_ad9835(SpiMock::new(wrapped.iter()))
+}
+
+pub fn new_ad5930(transactions: &[SpiTrans]) -> Ad983x<SpiMock, marker::Ad5930Ad5932> {
+    let wrapped: Vec<SpiTrans> = transactions
+        .iter()
+        .flat_map(|trans| {
+            [
+                SpiTrans::transaction_start(),
+                trans.clone(),
+                SpiTrans::transaction_end(),
+            ]
+        })
+        .collect();
+    Ad983x::new_ad5930(SpiMock::new(wrapped.iter()))
+}
+
+pub fn new_ad5932(transactions: &[SpiTrans]) -> Ad983x<SpiMock, marker::Ad5930Ad5932> {
+    let wrapped: Vec<SpiTrans> = transactions
+        .iter()
+        .flat_map(|trans| {
+            [
+                SpiTrans::transaction_start(),
+                trans.clone(),
+                SpiTrans::transaction_end(),
+            ]
+        })
+        .collect();
+    Ad983x::new_ad5932(SpiMock::new(wrapped.iter()))
+}
+
+pub fn new_any(variant: Variant, transactions: &[SpiTrans]) -> AnyAd983x<SpiMock> {
+    let wrapped: Vec<SpiTrans> = transactions
+        .iter()
+        .flat_map(|trans| {
+            [
+                SpiTrans::transaction_start(),
+                trans.clone(),
+                SpiTrans::transaction_end(),
+            ]
+        })
+        .collect();
+    AnyAd983x::new(SpiMock::new(wrapped.iter()), variant)
+}
+
+pub fn destroy_any(device: AnyAd983x<SpiMock>) {
+    device.destroy().done();
+}
+
 pub fn destroy<IC>(device: Ad983x<SpiMock, IC>) {
     device.destroy().done();
 }
+
+/// SPI device where some of the transactions fail
+pub struct FlakySpi {
+    pub mock: SpiMock,
+    /// Indices of the transactions that fail
+    failing: Vec<usize>,
+    count: usize,
+}
+
+impl ErrorType for FlakySpi {
+    type Error = ErrorKind;
+}
+
+impl SpiDevice for FlakySpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), ErrorKind> {
+        let index = self.count;
+        self.count += 1;
+        if self.failing.contains(&index) {
+            return Err(ErrorKind::Other);
+        }
+        self.mock.transaction(operations)
+    }
+}
+
+/// Create an AD9833 device where the transactions with the given indices fail.
+/// Only the successful transactions are expected.
+pub fn new_flaky_ad9833(
+    transactions: &[SpiTrans],
+    failing: &[usize],
+) -> Ad983x<FlakySpi, marker::Ad9833Ad9837> {
+    let wrapped: Vec<SpiTrans> = transactions
+        .iter()
+        .flat_map(|trans| {
+            [
+                SpiTrans::transaction_start(),
+                trans.clone(),
+                SpiTrans::transaction_end(),
+            ]
+        })
+        .collect();
+    Ad983x::new_ad9833(FlakySpi {
+        mock: SpiMock::new(wrapped.iter()),
+        failing: failing.to_vec(),
+        count: 0,
+    })
+}
+
+/// SPI device using 16-bit frames that records the words written
+#[derive(Debug, Default)]
+pub struct Spi16Recorder {
+    /// Words written in each transaction
+    pub transactions: Vec<Vec<u16>>,
+}
+
+impl ErrorType for Spi16Recorder {
+    type Error = Infallible;
+}
+
+impl SpiDevice<u16> for Spi16Recorder {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u16>]) -> Result<(), Infallible> {
+        let mut words = Vec::new();
+        for operation in operations {
+            match operation {
+                Operation::Read(read) => read.fill(0),
+                Operation::Write(write) => words.extend_from_slice(write),
+                Operation::Transfer(read, write) => {
+                    words.extend_from_slice(write);
+                    read.fill(0);
+                }
+                Operation::TransferInPlace(buffer) => {
+                    words.extend_from_slice(buffer);
+                    buffer.fill(0);
+                }
+                Operation::DelayNs(_) => (),
+            }
+        }
+        self.transactions.push(words);
+        Ok(())
+    }
+}
+
+/// Create an AD9833 device using 16-bit frames
+pub fn new_ad9833_16() -> Ad983x<Spi16<Spi16Recorder>, marker::Ad9833Ad9837> {
+    Ad983x::new_ad9833(Spi16::new(Spi16Recorder::default()))
+}
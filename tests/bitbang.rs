@@ -0,0 +1,153 @@
+use ad983x::{
+    bitbang::{BitBangError, BitBangSpi},
+    Ad983x, Error, FrequencyRegister as FreqReg,
+};
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{ErrorKind, ErrorType, OutputPin},
+};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Line {
+    Fsync,
+    Sclk,
+    Sdata,
+}
+
+type Edges = Rc<RefCell<Vec<(Line, bool)>>>;
+
+#[derive(Debug, PartialEq)]
+struct PinError;
+
+impl embedded_hal::digital::Error for PinError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+struct Pin {
+    line: Line,
+    edges: Edges,
+    failing: bool,
+}
+
+impl ErrorType for Pin {
+    type Error = PinError;
+}
+
+impl OutputPin for Pin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true)
+    }
+}
+
+impl Pin {
+    fn set(&mut self, level: bool) -> Result<(), PinError> {
+        if self.failing {
+            return Err(PinError);
+        }
+        self.edges.borrow_mut().push((self.line, level));
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct CountingDelay {
+    calls: u32,
+    total_ns: u32,
+}
+
+impl DelayNs for CountingDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.calls += 1;
+        self.total_ns += ns;
+    }
+}
+
+fn pins(edges: &Edges) -> (Pin, Pin, Pin) {
+    let pin = |line| Pin {
+        line,
+        edges: edges.clone(),
+        failing: false,
+    };
+    (pin(Line::Fsync), pin(Line::Sclk), pin(Line::Sdata))
+}
+
+/// Decode the words latched by the device on the SCLK falling edges
+/// while FSYNC is low.
+fn latched_words(edges: &Edges) -> Vec<u16> {
+    let (mut fsync, mut sclk, mut sdata) = (true, true, false);
+    let (mut word, mut bits) = (0u16, 0);
+    let mut words = Vec::new();
+    for &(line, level) in edges.borrow().iter() {
+        match line {
+            Line::Fsync => {
+                if !level {
+                    assert!(sclk, "SCLK must be high when FSYNC falls");
+                } else if !fsync {
+                    assert_eq!(16, bits, "FSYNC must frame 16 bits");
+                    words.push(word);
+                    word = 0;
+                    bits = 0;
+                }
+                fsync = level;
+            }
+            Line::Sclk => {
+                if sclk && !level && !fsync {
+                    word = word << 1 | sdata as u16;
+                    bits += 1;
+                }
+                sclk = level;
+            }
+            Line::Sdata => sdata = level,
+        }
+    }
+    assert!(fsync, "FSYNC must be high at the end");
+    assert!(sclk, "SCLK must idle high");
+    words
+}
+
+#[test]
+fn can_write_words() {
+    let edges = Edges::default();
+    let (fsync, sclk, sdata) = pins(&edges);
+    let mut dds = Ad983x::new_ad9833(BitBangSpi::new(fsync, sclk, sdata));
+    dds.reset().unwrap();
+    dds.set_frequency(FreqReg::F0, 0x1274).unwrap();
+    dds.enable().unwrap();
+    assert_eq!(
+        vec![0x0100, 0x2100, 0x5274, 0x4000, 0x2000],
+        latched_words(&edges)
+    );
+}
+
+#[test]
+fn waits_half_period_after_each_edge() {
+    let edges = Edges::default();
+    let (fsync, sclk, sdata) = pins(&edges);
+    let spi = BitBangSpi::new_with_delay(fsync, sclk, sdata, CountingDelay::default(), 500);
+    let mut dds = Ad983x::new_ad9833(spi);
+    dds.reset().unwrap();
+    let (_, _, _, delay) = dds.destroy().destroy();
+    assert_eq!(33, delay.calls);
+    assert_eq!(16_500, delay.total_ns);
+    assert_eq!(vec![0x0100], latched_words(&edges));
+}
+
+#[test]
+fn returns_pin_error() {
+    let edges = Edges::default();
+    let (fsync, mut sclk, sdata) = pins(&edges);
+    sclk.failing = true;
+    let mut dds = Ad983x::new_ad9833(BitBangSpi::new(fsync, sclk, sdata));
+    match dds.reset() {
+        Err(Error::Spi(BitBangError::Pin(PinError))) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert!(dds.is_desynchronized());
+}
@@ -0,0 +1,154 @@
+use ad983x::{
+    channels::{ChannelConfig, ChannelManager},
+    marker, Ad983x, Error, FrequencyRegister as FreqReg, OutputWaveform, PoweredDown,
+};
+use embedded_hal::spi::{self, ErrorKind, ErrorType, Operation, SpiDevice};
+
+#[derive(Debug, PartialEq)]
+struct SpiError;
+
+impl spi::Error for SpiError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// SPI device recording the words written
+#[derive(Debug, Default)]
+struct Recorder {
+    words: Vec<u16>,
+    failing: bool,
+}
+
+impl ErrorType for Recorder {
+    type Error = SpiError;
+}
+
+impl SpiDevice for Recorder {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), SpiError> {
+        if self.failing {
+            return Err(SpiError);
+        }
+        for operation in operations {
+            if let Operation::Write(bytes) = operation {
+                self.words.push(u16::from_be_bytes([bytes[0], bytes[1]]));
+            }
+        }
+        Ok(())
+    }
+}
+
+type Manager = ChannelManager<Recorder, marker::Ad9833Ad9837, 3>;
+
+fn new_manager(failing: &[usize]) -> Manager {
+    let channel = |index| {
+        Ad983x::new_ad9833(Recorder {
+            failing: failing.contains(&index),
+            ..Recorder::default()
+        })
+    };
+    ChannelManager::new([channel(0), channel(1), channel(2)])
+}
+
+fn words(manager: Manager) -> Vec<Vec<u16>> {
+    manager.destroy().map(|dds| dds.destroy().words).to_vec()
+}
+
+#[test]
+fn can_apply_config_table() {
+    let mut manager = new_manager(&[]);
+    let sine = ChannelConfig {
+        frequency: 0x1274,
+        enabled: true,
+        ..ChannelConfig::default()
+    };
+    let triangle = ChannelConfig {
+        phase: 1024,
+        waveform: OutputWaveform::Triangle,
+        ..sine
+    };
+    let off = ChannelConfig {
+        powered_down: PoweredDown::DacAndInternalClock,
+        ..ChannelConfig::default()
+    };
+    manager.apply(&[sine, triangle, off]).unwrap();
+    assert_eq!(
+        vec![
+            vec![0x2100, 0x5274, 0x4000, 0x2100, 0xC000, 0x2100, 0x2100, 0x2100, 0x2000],
+            vec![0x2100, 0x5274, 0x4000, 0x2100, 0xC400, 0x2100, 0x2102, 0x2102, 0x2002],
+            vec![0x2100, 0x4000, 0x4000, 0x2100, 0xC000, 0x2100, 0x2100, 0x21C0, 0x21C0],
+        ],
+        words(manager)
+    );
+}
+
+#[test]
+fn can_configure_single_channel() {
+    let mut manager = new_manager(&[]);
+    let config = ChannelConfig {
+        frequency: 1,
+        ..ChannelConfig::default()
+    };
+    manager.configure(1, &config).unwrap();
+    match manager.configure(3, &config) {
+        Err(Error::InvalidChannel { index: 3 }) => (),
+        _ => panic!("Should return Error::InvalidChannel"),
+    }
+    let words = words(manager);
+    assert!(words[0].is_empty() && words[2].is_empty());
+    assert_eq!(9, words[1].len());
+}
+
+#[test]
+fn cannot_configure_without_channels() {
+    let mut manager: ChannelManager<Recorder, marker::Ad9833Ad9837, 0> = ChannelManager::new([]);
+    match manager.configure(0, &ChannelConfig::default()) {
+        Err(Error::InvalidChannel { index: 0 }) => (),
+        _ => panic!("Should return Error::InvalidChannel"),
+    }
+}
+
+#[test]
+fn can_address_channels() {
+    let mut manager = new_manager(&[]);
+    manager.channel(2).unwrap().enable().unwrap();
+    assert!(manager.channel(3).is_none());
+    assert_eq!(vec![vec![], vec![], vec![0x0000]], words(manager));
+}
+
+#[test]
+fn can_run_bulk_operations() {
+    let mut manager = new_manager(&[]);
+    manager.set_frequency_all(FreqReg::F1, 2).unwrap();
+    manager.set_powered_down_all(PoweredDown::Dac).unwrap();
+    manager.enable_all().unwrap();
+    manager.disable_all().unwrap();
+    assert_eq!(
+        vec![vec![0x2100, 0x8002, 0x8000, 0x2140, 0x2040, 0x2140]; 3],
+        words(manager)
+    );
+}
+
+#[test]
+fn reports_errors_per_channel() {
+    let mut manager = new_manager(&[0, 2]);
+    let errors = match manager.enable_all() {
+        Err(errors) => errors,
+        _ => panic!("Should return ChannelErrors"),
+    };
+    assert_eq!(2, errors.count());
+    match errors.get(0) {
+        Some(Error::Spi(SpiError)) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert!(errors.get(1).is_none());
+    assert_eq!(
+        vec![0, 2],
+        errors
+            .iter()
+            .map(|(channel, _)| channel)
+            .collect::<Vec<_>>()
+    );
+    // The working channel is enabled anyway
+    assert_eq!(vec![vec![], vec![0x0000], vec![]], words(manager));
+}
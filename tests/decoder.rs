@@ -0,0 +1,243 @@
+use ad983x::decoder::{parse_csv, parse_hex, Command, Decoder, FrequencyPart, ParseError};
+use ad983x::{
+    ExtendedPhaseRegister, FrequencyRegister as FreqReg, IncrementInterval, IncrementIntervalBase,
+    IncrementIntervalMultiplier, PhaseRegister as PhaseReg, Variant,
+};
+
+fn decode_all(variant: Variant, words: &[u16]) -> Vec<String> {
+    let mut decoder = Decoder::new(variant);
+    words
+        .iter()
+        .map(|word| decoder.decode(*word).to_string())
+        .collect()
+}
+
+#[test]
+fn decodes_ad9832_commands() {
+    assert_eq!(
+        vec![
+            "SLEEP off, RESET on, CLR off",
+            "SYNC off, SELSRC on",
+            "defer [0x4] = 0x52",
+            "FREQ1 LSB = 0xB852",
+            "defer [0x6] = 0x1E",
+            "FREQ1 MSB = 0x051E",
+            "defer [0xE] = 0x00",
+            "PHASE3 = 1024",
+            "select: FSELECT=F1 PSELECT=P3",
+            "select: PSELECT=P2",
+            "unknown command 0x7000",
+        ],
+        decode_all(
+            Variant::Ad9835,
+            &[
+                0xD000, 0x9000, 0x3452, 0x25B8, 0x361E, 0x2705, 0x1E00, 0x0F04, 0x6E00, 0x4400,
+                0x7000
+            ]
+        )
+    );
+}
+
+#[test]
+fn decodes_ad9832_extended_phase() {
+    let mut decoder = Decoder::new(Variant::Ad9832);
+    decoder.decode(0x1C34);
+    assert_eq!(
+        Command::ExtendedPhase {
+            register: ExtendedPhaseRegister::P2,
+            value: 0x234
+        },
+        decoder.decode(0x0DF2)
+    );
+}
+
+#[test]
+fn decodes_ad5930_registers() {
+    assert_eq!(
+        vec![
+            "control: B24 on, DAC ENABLE on, SINE/TRI off, MSBOUTEN off, CW/BURST on, \
+             INT/EXT INCR on, SYNCSEL off, SYNCOUTEN on",
+            "FSTART LSB = 0x234",
+            "FSTART MSB = 0x001",
+            "DELTA_F LSB = 0x010",
+            "DELTA_F MSB = 0x000, negative",
+            "NINCR = 100",
+            "TINT = 3 x100 MCLK periods",
+            "TINT = 2047 x5 output cycles",
+            "unknown command 0xF000",
+        ],
+        decode_all(
+            Variant::Ad5930,
+            &[0x0CA7, 0xC234, 0xD001, 0x2010, 0x3800, 0x1064, 0x7003, 0x4FFF, 0xF000]
+        )
+    );
+}
+
+#[test]
+fn decodes_ad5932_sweep_interval() {
+    let mut decoder = Decoder::new(Variant::Ad5932);
+    assert_eq!(
+        Command::IncrementInterval(IncrementInterval {
+            base: IncrementIntervalBase::MclkPeriods,
+            multiplier: IncrementIntervalMultiplier::X500,
+            count: 2,
+        }),
+        decoder.decode(0x7802)
+    );
+    match decoder.decode(0x0E00) {
+        Command::SweepControl(control) => assert_eq!(None, control.cw_burst),
+        _ => panic!("Should decode the control register"),
+    }
+}
+
+#[test]
+fn decodes_28_bit_frequency_write() {
+    assert_eq!(
+        vec![
+            "control: B28 on, HLB off, FSELECT=F1, PSELECT=P0, RESET off, SLEEP1 off, \
+             SLEEP12 off, OPBITEN off, DIV2 off, MODE off",
+            "FREQ1 LSB = 0x1274",
+            "FREQ1 MSB = 0x0001",
+            "PHASE1 = 1024",
+        ],
+        decode_all(Variant::Ad9837, &[0x2800, 0x9274, 0x8001, 0xE400])
+    );
+}
+
+#[test]
+fn decodes_hlb_frequency_write() {
+    let mut decoder = Decoder::new(Variant::Ad9833);
+    decoder.decode(0x1000);
+    assert_eq!(
+        Command::Frequency {
+            register: FreqReg::F0,
+            part: FrequencyPart::Msb,
+            value: 0x3FFF
+        },
+        decoder.decode(0x7FFF)
+    );
+    decoder.decode(0x0000);
+    assert_eq!(
+        Command::Frequency {
+            register: FreqReg::F0,
+            part: FrequencyPart::Lsb,
+            value: 0x3FFF
+        },
+        decoder.decode(0x7FFF)
+    );
+}
+
+#[test]
+fn decodes_phase_d13() {
+    let mut decoder = Decoder::new(Variant::Ad9833);
+    assert_eq!(
+        Command::Phase {
+            register: PhaseReg::P0,
+            value: 0x123
+        },
+        decoder.decode(0xD123)
+    );
+}
+
+#[test]
+fn decodes_variant_specific_bits() {
+    assert_eq!(
+        vec![
+            "control: B28 off, HLB off, FSELECT=F0, PSELECT=P0, PIN/SW on, RESET off, \
+             SLEEP1 off, SLEEP12 off, OPBITEN on, SIGN/PIB on, DIV2 on, MODE off"
+        ],
+        decode_all(Variant::Ad9834, &[0x0238])
+    );
+    assert_eq!(
+        vec![
+            "control: B28 off, HLB off, FSELECT=F0, PSELECT=P0, RESET off, SLEEP1 off, \
+             SLEEP12 off, OPBITEN on, DIV2 on, MODE off, reserved bits set: 0x0210"
+        ],
+        decode_all(Variant::Ad9833, &[0x0238])
+    );
+}
+
+#[test]
+fn decodes_bytes() {
+    let mut decoder = Decoder::new(Variant::Ad9833);
+    let commands: Vec<Command> = decoder.decode_bytes(&[0xC0, 0x01, 0xE0]).collect();
+    assert_eq!(
+        vec![Command::Phase {
+            register: PhaseReg::P0,
+            value: 1
+        }],
+        commands
+    );
+}
+
+#[test]
+fn parses_hex_dump() {
+    let dump = "00000000: 21 00 0x52,0x74 # comment\n4000 C400\n";
+    assert_eq!(Ok(vec![0x2100, 0x5274, 0x4000, 0xC400]), parse_hex(dump));
+}
+
+const DUMP_WORDS: [u16; 9] = [
+    0x2100, 0x5274, 0x4000, 0xC400, 0x0800, 0x3A23, 0x7C20, 0x2041, 0x4243,
+];
+
+#[test]
+fn parses_xxd_dump() {
+    let dump = "00000000: 2100 5274 4000 c400 0800 3a23 7c20 2041  !.Rt@.....:#|  A\n\
+                00000010: 4243                                     BC\n";
+    assert_eq!(Ok(DUMP_WORDS.to_vec()), parse_hex(dump));
+}
+
+#[test]
+fn parses_xxd_byte_dump() {
+    let dump = "00000000: 21 00 52 74 40 00 c4 00 08 00 3a 23 7c 20 20 41  !.Rt@.....:#|  A\n\
+                00000010: 42 43                                            BC\n";
+    assert_eq!(Ok(DUMP_WORDS.to_vec()), parse_hex(dump));
+}
+
+#[test]
+fn parses_hexdump_canonical_dump() {
+    let dump = "00000000  21 00 52 74 40 00 c4 00  08 00 3a 23 7c 20 20 41  |!.Rt@.....:#|  A|\n\
+                00000010  42 43                                             |BC|\n\
+                00000012\n";
+    assert_eq!(Ok(DUMP_WORDS.to_vec()), parse_hex(dump));
+}
+
+#[test]
+fn parses_prefixed_word_at_line_start() {
+    assert_eq!(Ok(vec![0x2100]), parse_hex("0x2100"));
+}
+
+#[test]
+fn cannot_parse_odd_hex_bytes() {
+    assert_eq!(Err(ParseError::OddByteCount), parse_hex("21 00 52"));
+}
+
+#[test]
+fn cannot_parse_invalid_hex() {
+    assert_eq!(Err(ParseError::InvalidValue(2)), parse_hex("2100\n21 0g"));
+}
+
+#[test]
+fn parses_sigrok_csv() {
+    let csv = "; CSV, generated by libsigrok4DSL\n\
+               Time [s],Packet ID,MOSI,MISO\n\
+               0.000001,0,0x21,0x00\n\
+               0.000002,0,0x00,0x00\n\
+               0.000003,1,,\n\
+               0.000004,2,0x52,0x00\n\
+               0.000005,2,0x74,0x00\n";
+    assert_eq!(Ok(vec![0x2100, 0x5274]), parse_csv(csv));
+}
+
+#[test]
+fn parses_pulseview_annotations() {
+    let csv = "\"Start\",\"Annotation\"\n\
+               \"10\",\"SPI: MOSI data: 21\"\n\
+               \"20\",\"SPI: MOSI data: 00\"\n";
+    assert_eq!(Ok(vec![0x2100]), parse_csv(csv));
+}
+
+#[test]
+fn cannot_parse_empty_csv() {
+    assert_eq!(Err(ParseError::MissingData), parse_csv("Time,MOSI\n"));
+}
@@ -0,0 +1,132 @@
+use ad983x::{
+    eh02::{Eh02Error, Eh02Spi},
+    Ad983x, Error, FrequencyRegister as FreqReg,
+};
+use embedded_hal_02::{blocking::spi::Write, digital::v2::OutputPin};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Fsync(bool),
+    Write(Vec<u8>),
+}
+
+type Events = Rc<RefCell<Vec<Event>>>;
+
+#[derive(Debug, PartialEq)]
+struct SpiError;
+
+#[derive(Debug, PartialEq)]
+struct PinError;
+
+struct Spi {
+    events: Events,
+    failing: bool,
+}
+
+impl Write<u8> for Spi {
+    type Error = SpiError;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), SpiError> {
+        if self.failing {
+            return Err(SpiError);
+        }
+        self.events.borrow_mut().push(Event::Write(words.to_vec()));
+        Ok(())
+    }
+}
+
+struct Pin {
+    events: Events,
+    failing: bool,
+}
+
+impl OutputPin for Pin {
+    type Error = PinError;
+
+    fn set_low(&mut self) -> Result<(), PinError> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), PinError> {
+        self.set(true)
+    }
+}
+
+impl Pin {
+    fn set(&mut self, level: bool) -> Result<(), PinError> {
+        if self.failing {
+            return Err(PinError);
+        }
+        self.events.borrow_mut().push(Event::Fsync(level));
+        Ok(())
+    }
+}
+
+fn new_interface(events: &Events) -> Eh02Spi<Spi, Pin> {
+    let spi = Spi {
+        events: events.clone(),
+        failing: false,
+    };
+    let cs = Pin {
+        events: events.clone(),
+        failing: false,
+    };
+    Eh02Spi::new(spi, cs)
+}
+
+fn framed(words: &[[u8; 2]]) -> Vec<Event> {
+    words
+        .iter()
+        .flat_map(|word| {
+            [
+                Event::Fsync(false),
+                Event::Write(word.to_vec()),
+                Event::Fsync(true),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn frames_each_word_with_fsync() {
+    let events = Events::default();
+    let mut dds = Ad983x::new_ad9833(new_interface(&events));
+    dds.reset().unwrap();
+    dds.set_frequency(FreqReg::F0, 0x1274).unwrap();
+    dds.enable().unwrap();
+    assert_eq!(
+        framed(&[[1, 0], [0x21, 0], [0x52, 0x74], [0x40, 0], [0x20, 0]]),
+        *events.borrow()
+    );
+}
+
+#[test]
+fn sets_fsync_high_after_spi_error() {
+    let events = Events::default();
+    let (mut spi, cs) = new_interface(&events).destroy();
+    spi.failing = true;
+    let mut dds = Ad983x::new_ad9833(Eh02Spi::new(spi, cs));
+    match dds.reset() {
+        Err(Error::Spi(Eh02Error::Spi(SpiError))) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert!(dds.is_desynchronized());
+    assert_eq!(
+        vec![Event::Fsync(false), Event::Fsync(true)],
+        *events.borrow()
+    );
+}
+
+#[test]
+fn returns_pin_error() {
+    let events = Events::default();
+    let (spi, mut cs) = new_interface(&events).destroy();
+    cs.failing = true;
+    let mut dds = Ad983x::new_ad9833(Eh02Spi::new(spi, cs));
+    match dds.reset() {
+        Err(Error::Spi(Eh02Error::Pin(PinError))) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert!(events.borrow().is_empty());
+}
@@ -0,0 +1,241 @@
+use ad983x::{
+    emulator::{Emulator, Violation},
+    renderer::Renderer,
+    Ad983x, FrequencyRegister as FreqReg, OutputWaveform as OW, PhaseRegister as PhaseReg,
+    PoweredDown as PD, SignBitOutput as SBO, Variant,
+};
+use embedded_hal::spi::SpiDevice;
+
+fn write_words(emulator: &mut Emulator, words: &[u16]) {
+    for word in words {
+        emulator.write(&word.to_be_bytes()).unwrap();
+    }
+}
+
+#[test]
+fn cannot_emulate_ad9832() {
+    assert!(Emulator::new(Variant::Ad9832).is_none());
+}
+
+#[test]
+fn driver_configures_emulated_ad9833() {
+    let emulator = Emulator::new(Variant::Ad9833).unwrap();
+    let mut dds = Ad983x::new_ad9833(emulator.clone());
+    dds.reset().unwrap();
+    assert!(emulator.is_reset());
+    dds.set_frequency(FreqReg::F1, 0x9AB_CDEF).unwrap();
+    dds.select_frequency(FreqReg::F1).unwrap();
+    dds.set_phase(PhaseReg::P1, 0xABC).unwrap();
+    dds.select_phase(PhaseReg::P1).unwrap();
+    dds.set_output_waveform(OW::SquareMsbOfDacDiv2).unwrap();
+    dds.set_powered_down(PD::Dac).unwrap();
+    dds.enable().unwrap();
+
+    assert!(!emulator.is_reset());
+    assert_eq!(0x9AB_CDEF, emulator.frequency(FreqReg::F1));
+    assert_eq!(0, emulator.frequency(FreqReg::F0));
+    assert_eq!(FreqReg::F1, emulator.selected_frequency());
+    assert_eq!(0xABC, emulator.phase(PhaseReg::P1));
+    assert_eq!(PhaseReg::P1, emulator.selected_phase());
+    assert_eq!(OW::SquareMsbOfDacDiv2, emulator.output_waveform());
+    assert_eq!(PD::Dac, emulator.powered_down());
+    assert!(emulator.violations().is_empty());
+}
+
+#[test]
+fn driver_configures_emulated_ad9834_sign_bit_output() {
+    let emulator = Emulator::new(Variant::Ad9834).unwrap();
+    let mut dds = Ad983x::new_ad9834(emulator.clone());
+    dds.reset().unwrap();
+    dds.set_output_waveform(OW::Triangle).unwrap();
+    dds.set_sign_bit_output(SBO::SquareMsbOfDac).unwrap();
+    assert_eq!(OW::Sinusoidal, emulator.output_waveform());
+    assert_eq!(SBO::SquareMsbOfDac, emulator.sign_bit_output());
+    assert!(emulator.violations().is_empty());
+}
+
+#[test]
+fn driver_sets_frequency_halves() {
+    let emulator = Emulator::new(Variant::Ad9837).unwrap();
+    let mut dds = Ad983x::new_ad9837(emulator.clone());
+    dds.set_frequency(FreqReg::F0, 0x9AB_CDEF).unwrap();
+    dds.set_frequency_msb(FreqReg::F0, 0x1234).unwrap();
+    assert_eq!(0x48D_0DEF, emulator.frequency(FreqReg::F0));
+    dds.set_frequency_lsb(FreqReg::F0, 0x0FFF).unwrap();
+    assert_eq!(0x48D_0FFF, emulator.frequency(FreqReg::F0));
+    assert!(emulator.violations().is_empty());
+}
+
+#[test]
+fn decodes_phase_d13() {
+    let mut emulator = Emulator::new(Variant::Ad9833).unwrap();
+    write_words(&mut emulator, &[0xC123, 0xE456]);
+    assert_eq!(0x123, emulator.phase(PhaseReg::P0));
+    assert_eq!(0x456, emulator.phase(PhaseReg::P1));
+}
+
+#[test]
+fn flags_odd_number_of_28_bit_halves() {
+    let mut emulator = Emulator::new(Variant::Ad9833).unwrap();
+    // B28 set, then only the LSBs of FREQ0 followed by a FREQ1 write.
+    write_words(&mut emulator, &[0x2000, 0x4001, 0x8002]);
+    assert_eq!(
+        vec![
+            Violation::IncompleteFrequencyWrite(FreqReg::F0),
+            Violation::IncompleteFrequencyWrite(FreqReg::F1)
+        ],
+        emulator.violations()
+    );
+    assert_eq!(0, emulator.frequency(FreqReg::F0));
+    emulator.clear_violations();
+    write_words(&mut emulator, &[0x8000]);
+    assert!(emulator.violations().is_empty());
+    assert_eq!(2, emulator.frequency(FreqReg::F1));
+}
+
+#[test]
+fn flags_reserved_bits() {
+    let mut emulator = Emulator::new(Variant::Ad9833).unwrap();
+    write_words(&mut emulator, &[0x0200, 0x0022]);
+    assert_eq!(
+        vec![
+            Violation::ReservedBitSet(0x0200),
+            Violation::ReservedOutputConfiguration(0x0022)
+        ],
+        emulator.violations()
+    );
+}
+
+#[test]
+fn allows_pin_sw_on_ad9838() {
+    let mut emulator = Emulator::new(Variant::Ad9838).unwrap();
+    write_words(&mut emulator, &[0x0200]);
+    assert!(emulator.violations().is_empty());
+}
+
+#[test]
+fn flags_incomplete_word() {
+    let mut emulator = Emulator::new(Variant::Ad9833).unwrap();
+    emulator.write(&[0x01, 0x00, 0x20]).unwrap();
+    assert!(emulator.is_reset());
+    assert_eq!(vec![Violation::IncompleteWord], emulator.violations());
+}
+
+fn new_rendered_ad9833() -> (Emulator, Ad983x<Emulator, ad983x::marker::Ad9833Ad9837>) {
+    let emulator = Emulator::new(Variant::Ad9833).unwrap();
+    let mut dds = Ad983x::new_ad9833(emulator.clone());
+    dds.reset().unwrap();
+    dds.enable().unwrap();
+    (emulator, dds)
+}
+
+#[test]
+fn renderer_rejects_zero_frequencies() {
+    assert!(Renderer::new(0, 1_000_000).is_none());
+    assert!(Renderer::new(1_000_000, 0).is_none());
+}
+
+#[test]
+fn renderer_advances_phase_accumulator() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    dds.set_frequency(FreqReg::F0, 1 << 24).unwrap();
+    let mut renderer = Renderer::new(2_000_000, 1_000_000).unwrap();
+    renderer.next_sample(&emulator);
+    assert_eq!(1 << 25, renderer.accumulator());
+    let mut samples = [0.0; 7];
+    renderer.render(&emulator, &mut samples);
+    assert_eq!(0, renderer.accumulator());
+}
+
+#[test]
+fn renderer_holds_accumulator_in_reset() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    dds.set_frequency(FreqReg::F0, 1 << 24).unwrap();
+    dds.disable().unwrap();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    let mut samples = [1.0; 4];
+    renderer.render(&emulator, &mut samples);
+    assert_eq!(0, renderer.accumulator());
+    assert!(samples.iter().all(|s| s.abs() < 0.01));
+}
+
+#[test]
+fn renderer_quantizes_sine() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    dds.set_frequency(FreqReg::F0, 1 << 26).unwrap();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    let mut samples = [0.0; 4];
+    renderer.render(&emulator, &mut samples);
+    // Sample after a quarter period is the DAC full-scale
+    assert_eq!(1.0, samples[0]);
+    assert_eq!(-1.0, samples[2]);
+    // Half scale is not representable with 10 bits
+    assert_eq!(1.0 / 1023.0, samples[1]);
+}
+
+#[test]
+fn renderer_applies_phase_step() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    assert!(renderer.next_sample(&emulator).vout.abs() < 0.01);
+    dds.set_phase(PhaseReg::P1, 1024).unwrap();
+    dds.select_phase(PhaseReg::P1).unwrap();
+    assert_eq!(1.0, renderer.next_sample(&emulator).vout);
+}
+
+#[test]
+fn renderer_keeps_phase_on_fsk_switch() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    dds.set_frequency(FreqReg::F0, 1 << 24).unwrap();
+    dds.set_frequency(FreqReg::F1, 1 << 25).unwrap();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    renderer.render(&emulator, &mut [0.0; 3]);
+    dds.select_frequency(FreqReg::F1).unwrap();
+    renderer.next_sample(&emulator);
+    assert_eq!(5 << 24, renderer.accumulator());
+}
+
+#[test]
+fn renderer_renders_triangle() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    dds.set_frequency(FreqReg::F0, 1 << 26).unwrap();
+    dds.set_output_waveform(OW::Triangle).unwrap();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    let mut samples = [0.0; 4];
+    renderer.render(&emulator, &mut samples);
+    assert_eq!(1.0, samples[1]);
+    assert_eq!(-1.0, samples[3]);
+    assert!((samples[0] - 1.0 / 1023.0).abs() < 1e-6);
+    assert!((samples[2] + 1.0 / 1023.0).abs() < 1e-6);
+}
+
+#[test]
+fn renderer_renders_square_waves() {
+    let (emulator, mut dds) = new_rendered_ad9833();
+    dds.set_frequency(FreqReg::F0, 1 << 26).unwrap();
+    dds.set_output_waveform(OW::SquareMsbOfDac).unwrap();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    let mut samples = [0.0; 8];
+    renderer.render(&emulator, &mut samples);
+    assert_eq!([1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0], samples);
+
+    dds.set_output_waveform(OW::SquareMsbOfDacDiv2).unwrap();
+    renderer.render(&emulator, &mut samples);
+    assert_eq!([-1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0, -1.0], samples);
+}
+
+#[test]
+fn renderer_renders_sign_bit_output() {
+    let emulator = Emulator::new(Variant::Ad9834).unwrap();
+    let mut dds = Ad983x::new_ad9834(emulator.clone());
+    dds.reset().unwrap();
+    dds.set_frequency(FreqReg::F0, 1 << 26).unwrap();
+    dds.set_sign_bit_output(SBO::SquareMsbOfDac).unwrap();
+    dds.set_powered_down(PD::Dac).unwrap();
+    dds.enable().unwrap();
+    let mut renderer = Renderer::new(1_000_000, 1_000_000).unwrap();
+    let sample = renderer.next_sample(&emulator);
+    assert_eq!(0.0, sample.vout);
+    assert_eq!(Some(true), sample.sign_bit_out);
+    assert_eq!(Some(false), renderer.next_sample(&emulator).sign_bit_out);
+}
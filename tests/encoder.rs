@@ -0,0 +1,119 @@
+use ad983x::{
+    encoder::Encoder, ControlSource, ControlWord, Error, FrequencyRegister as FreqReg,
+    OutputWaveform, PhaseRegister as PhaseReg, PoweredDown, SignBitOutput, Variant,
+};
+
+#[test]
+fn cannot_encode_ad9832() {
+    assert!(Encoder::new(Variant::Ad9832).is_none());
+}
+
+#[test]
+fn encodes_control_only_once_for_28_bit_writes() {
+    let mut encoder = Encoder::new(Variant::Ad9837).unwrap();
+    let words = encoder.set_frequency(FreqReg::F1, 0x9AB_CDEF).unwrap();
+    assert_eq!(&[0x2100, 0x8DEF, 0xA6AF], words.as_slice());
+    let words = encoder.set_frequency(FreqReg::F0, 1).unwrap();
+    assert_eq!(&[0x4001, 0x4000], words.as_slice());
+}
+
+#[test]
+fn encodes_14_bit_writes() {
+    let mut encoder = Encoder::new(Variant::Ad9833).unwrap();
+    let words = encoder.set_frequency_msb(FreqReg::F0, 0x3FFF).unwrap();
+    assert_eq!(&[0x1100, 0x7FFF], words.as_slice());
+    let words = encoder.set_frequency_lsb(FreqReg::F0, 1).unwrap();
+    assert_eq!(&[0x0100, 0x4001], words.as_slice());
+}
+
+#[test]
+fn encodes_phase_and_selection() {
+    let mut encoder = Encoder::new(Variant::Ad9834).unwrap();
+    assert_eq!(
+        &[0xE123],
+        encoder.set_phase(PhaseReg::P1, 0x123).unwrap().as_slice()
+    );
+    assert_eq!(&[0x0500], encoder.select_phase(PhaseReg::P1).as_slice());
+    assert_eq!(&[0x0D00], encoder.select_frequency(FreqReg::F1).as_slice());
+    assert_eq!(&[0x0C00], encoder.enable().as_slice());
+    assert_eq!(&[0x0D00], encoder.reset().as_slice());
+}
+
+#[test]
+fn encodes_big_endian_bytes() {
+    let mut encoder = Encoder::new(Variant::Ad9833).unwrap();
+    let words = encoder.set_frequency(FreqReg::F0, 0x1274).unwrap();
+    let bytes: Vec<u8> = words.bytes().collect();
+    assert_eq!(vec![0x21, 0x00, 0x52, 0x74, 0x40, 0x00], bytes);
+}
+
+#[test]
+fn cannot_encode_too_wide_values() {
+    let mut encoder = Encoder::new(Variant::Ad9833).unwrap();
+    match encoder.set_frequency(FreqReg::F0, 1 << 28) {
+        Err(Error::ValueTooWide { bits: 28 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
+    match encoder.set_frequency_msb(FreqReg::F0, 1 << 14) {
+        Err(Error::ValueTooWide { bits: 14 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
+    match encoder.set_phase(PhaseReg::P0, 1 << 12) {
+        Err(Error::ValueTooWide { bits: 12 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
+    assert_eq!(ControlWord::new().reset(true), encoder.control_word());
+}
+
+#[test]
+fn cannot_encode_pin_control_on_ad9833() {
+    let mut encoder = Encoder::new(Variant::Ad9833).unwrap();
+    match encoder.write_control_word(ControlWord::new().sign_pib(true)) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    match encoder.write_control_word(ControlWord::new().opbiten(true).mode(true)) {
+        Err(Error::UnsupportedWaveform(OutputWaveform::Triangle)) => (),
+        _ => panic!("Should return Error::UnsupportedWaveform"),
+    }
+}
+
+#[test]
+fn encodes_power_down_and_waveforms_ad9833() {
+    let mut encoder = Encoder::new(Variant::Ad9833).unwrap();
+    let words = encoder.set_powered_down(PoweredDown::Dac);
+    assert_eq!(&[0x0140], words.as_slice());
+    let words = encoder
+        .set_output_waveform(OutputWaveform::SquareMsbOfDac)
+        .unwrap();
+    assert_eq!(&[0x0168], words.as_slice());
+    match encoder.set_control_source(ControlSource::HardwarePins) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    match encoder.set_sign_bit_output(SignBitOutput::Comparator) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+}
+
+#[test]
+fn encodes_sign_bit_output_and_control_source_ad9834() {
+    let mut encoder = Encoder::new(Variant::Ad9834).unwrap();
+    match encoder.set_output_waveform(OutputWaveform::SquareMsbOfDac) {
+        Err(Error::UnsupportedWaveform(OutputWaveform::SquareMsbOfDac)) => (),
+        _ => panic!("Should return Error::UnsupportedWaveform"),
+    }
+    let words = encoder
+        .set_output_waveform(OutputWaveform::Triangle)
+        .unwrap();
+    assert_eq!(&[0x0102], words.as_slice());
+    let words = encoder
+        .set_sign_bit_output(SignBitOutput::SquareMsbOfDac)
+        .unwrap();
+    assert_eq!(&[0x0128], words.as_slice());
+    let words = encoder
+        .set_control_source(ControlSource::HardwarePins)
+        .unwrap();
+    assert_eq!(&[0x0328], words.as_slice());
+}
@@ -0,0 +1,299 @@
+use ad983x::{
+    group::{GroupError, SyncGroup},
+    marker, Ad983x, ControlSource, Error, FrequencyRegister as FreqReg, OutputWaveform,
+    PhaseRegister as PhaseReg,
+};
+use embedded_hal::{
+    digital::{self, OutputPin},
+    spi::{self, ErrorKind, ErrorType, Operation, SpiDevice},
+};
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+/// Destination of a word or RESET pin level
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Event {
+    Word(usize, u16),
+    Broadcast(u16),
+    ResetPin(bool),
+}
+
+type Events = Rc<RefCell<Vec<Event>>>;
+
+const BROADCAST: usize = usize::MAX;
+
+#[derive(Debug, PartialEq)]
+struct SpiError;
+
+impl spi::Error for SpiError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// SPI device recording the words written to a device or to all of them
+struct Recorder {
+    index: usize,
+    events: Events,
+    failing: bool,
+}
+
+impl ErrorType for Recorder {
+    type Error = SpiError;
+}
+
+impl SpiDevice for Recorder {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), SpiError> {
+        if self.failing {
+            return Err(SpiError);
+        }
+        for operation in operations {
+            if let Operation::Write(bytes) = operation {
+                let word = u16::from_be_bytes([bytes[0], bytes[1]]);
+                let event = if self.index == BROADCAST {
+                    Event::Broadcast(word)
+                } else {
+                    Event::Word(self.index, word)
+                };
+                self.events.borrow_mut().push(event);
+            }
+        }
+        Ok(())
+    }
+}
+
+struct ResetPin {
+    events: Events,
+}
+
+impl digital::ErrorType for ResetPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for ResetPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.events.borrow_mut().push(Event::ResetPin(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.events.borrow_mut().push(Event::ResetPin(true));
+        Ok(())
+    }
+}
+
+fn recorder(events: &Events, index: usize) -> Recorder {
+    Recorder {
+        index,
+        events: events.clone(),
+        failing: false,
+    }
+}
+
+fn new_ad9833_pair(events: &Events) -> [Ad983x<Recorder, marker::Ad9833Ad9837>; 2] {
+    [
+        Ad983x::new_ad9833(recorder(events, 0)),
+        Ad983x::new_ad9833(recorder(events, 1)),
+    ]
+}
+
+#[test]
+fn loads_devices_in_reset_and_starts_with_broadcast() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    let mut group = SyncGroup::new(&mut devices);
+    group
+        .load(|index, dds| {
+            dds.set_frequency(FreqReg::F0, 0x1274)?;
+            dds.set_phase(PhaseReg::P0, index as u16 * 1024)
+        })
+        .unwrap();
+    group.start(&mut recorder(&events, BROADCAST)).unwrap();
+    let expected = vec![
+        Event::Word(0, 0x0100),
+        Event::Word(0, 0x2100),
+        Event::Word(0, 0x5274),
+        Event::Word(0, 0x4000),
+        Event::Word(0, 0xC000),
+        Event::Word(1, 0x0100),
+        Event::Word(1, 0x2100),
+        Event::Word(1, 0x5274),
+        Event::Word(1, 0x4000),
+        Event::Word(1, 0xC400),
+        Event::Broadcast(0x2000),
+    ];
+    assert_eq!(expected, *events.borrow());
+    assert!(devices
+        .iter()
+        .all(|dds| dds.control_word().bits() == 0x2000));
+}
+
+#[test]
+fn keeps_devices_in_reset_while_loading() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    let mut group = SyncGroup::new(&mut devices);
+    group.load(|_, dds| dds.enable()).unwrap();
+    assert_eq!(
+        vec![
+            Event::Word(0, 0x0100),
+            Event::Word(0, 0x0000),
+            Event::Word(0, 0x0100),
+            Event::Word(1, 0x0100),
+            Event::Word(1, 0x0000),
+            Event::Word(1, 0x0100),
+        ],
+        *events.borrow()
+    );
+}
+
+#[test]
+fn cannot_start_with_different_control_settings() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    let mut group = SyncGroup::new(&mut devices);
+    group
+        .load(|index, dds| match index {
+            1 => dds.set_output_waveform(OutputWaveform::Triangle),
+            _ => Ok(()),
+        })
+        .unwrap();
+    events.borrow_mut().clear();
+    match group.start(&mut recorder(&events, BROADCAST)) {
+        Err(GroupError::ControlMismatch { index: 1 }) => (),
+        _ => panic!("Should return GroupError::ControlMismatch"),
+    }
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn reports_failing_device_index() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    devices[1] = Ad983x::new_ad9833(Recorder {
+        failing: true,
+        ..recorder(&events, 1)
+    });
+    let mut group = SyncGroup::new(&mut devices);
+    match group.load(|_, _| Ok(())) {
+        Err(GroupError::Device {
+            index: 1,
+            error: Error::Spi(SpiError),
+        }) => (),
+        _ => panic!("Should return GroupError::Device"),
+    }
+}
+
+#[test]
+fn can_broadcast_words() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    let mut group = SyncGroup::new(&mut devices);
+    let mut broadcast = recorder(&events, BROADCAST);
+    group
+        .broadcast(&mut broadcast, &[0x2102, 0x5274, 0x4000])
+        .unwrap();
+    assert_eq!(
+        vec![
+            Event::Broadcast(0x2102),
+            Event::Broadcast(0x5274),
+            Event::Broadcast(0x4000),
+        ],
+        *events.borrow()
+    );
+    for dds in group.devices() {
+        assert_eq!(0x2102, dds.control_word().bits());
+    }
+}
+
+#[test]
+fn desynchronizes_devices_after_broadcast_error() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    let mut group = SyncGroup::new(&mut devices);
+    let mut broadcast = Recorder {
+        failing: true,
+        ..recorder(&events, BROADCAST)
+    };
+    match group.broadcast(&mut broadcast, &[0x2000]) {
+        Err(GroupError::Broadcast(SpiError)) => (),
+        _ => panic!("Should return GroupError::Broadcast"),
+    }
+    assert!(devices.iter().all(|dds| dds.is_desynchronized()));
+}
+
+#[test]
+fn resyncs_devices_with_broadcast_control_word() {
+    let events = Events::default();
+    let mut devices = new_ad9833_pair(&events);
+    let mut group = SyncGroup::new(&mut devices);
+    let mut failing = Recorder {
+        failing: true,
+        ..recorder(&events, BROADCAST)
+    };
+    group
+        .broadcast(&mut failing, &[0x2000])
+        .expect_err("Should return error");
+    let mut broadcast = recorder(&events, BROADCAST);
+    group.broadcast(&mut broadcast, &[0x5274]).unwrap();
+    assert!(group.devices().iter().all(|dds| dds.is_desynchronized()));
+    group.broadcast(&mut broadcast, &[0x2000]).unwrap();
+    assert!(devices.iter().all(|dds| !dds.is_desynchronized()));
+}
+
+#[test]
+fn can_start_ad9834_with_reset_pin() {
+    let events = Events::default();
+    let mut devices = [
+        Ad983x::new_ad9834(recorder(&events, 0)),
+        Ad983x::new_ad9838(recorder(&events, 1)),
+    ];
+    let mut reset_pin = ResetPin {
+        events: events.clone(),
+    };
+    let mut group = SyncGroup::new(&mut devices);
+    group
+        .load_with_reset_pin(&mut reset_pin, |_, dds| dds.set_phase(PhaseReg::P1, 1))
+        .unwrap();
+    group.start_with_reset_pin(&mut reset_pin).unwrap();
+    assert_eq!(
+        vec![
+            Event::ResetPin(true),
+            Event::Word(0, 0x0200),
+            Event::Word(0, 0xE001),
+            Event::Word(1, 0x0200),
+            Event::Word(1, 0xE001),
+            Event::ResetPin(false),
+        ],
+        *events.borrow()
+    );
+    // The hardware pin control source stays selected
+    assert!(devices
+        .iter()
+        .all(|dds| dds.control_word().bits() & 0x0200 != 0));
+}
+
+#[test]
+fn keeps_running_after_switching_back_to_software_control() {
+    let events = Events::default();
+    let mut devices = [
+        Ad983x::new_ad9834(recorder(&events, 0)),
+        Ad983x::new_ad9838(recorder(&events, 1)),
+    ];
+    let mut reset_pin = ResetPin {
+        events: events.clone(),
+    };
+    let mut group = SyncGroup::new(&mut devices);
+    group
+        .load_with_reset_pin(&mut reset_pin, |_, dds| dds.reset())
+        .unwrap();
+    group.start_with_reset_pin(&mut reset_pin).unwrap();
+    events.borrow_mut().clear();
+    for dds in devices.iter_mut() {
+        dds.set_control_source(ControlSource::Software).unwrap();
+        assert_eq!(0x0000, dds.control_word().bits());
+    }
+    assert_eq!(
+        vec![Event::Word(0, 0x0000), Event::Word(1, 0x0000)],
+        *events.borrow()
+    );
+}
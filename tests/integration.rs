@@ -1,11 +1,19 @@
 use ad983x::{
-    ControlSource, FrequencyRegister as FreqReg, OutputWaveform as OW, PhaseRegister as PhaseReg,
-    PoweredDown as PD, SignBitOutput as SBO,
+    ControlSource, ControlWord, Dds, Error, ExtendedPhaseRegister as ExtPhaseReg,
+    FrequencyRegister as FreqReg, IncrementInterval, IncrementIntervalBase,
+    IncrementIntervalMultiplier, IncrementTrigger, OutputWaveform as OW, PhaseRegister as PhaseReg,
+    PoweredDown as PD, RetryPolicy, SignBitOutput as SBO, SquareWave, SquareWavePin, SweepConfig,
+    SyncOutput, Variant,
 };
-use embedded_hal_mock::spi::Transaction as SpiTrans;
+use embedded_hal::spi::ErrorKind as SpiErrorKind;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 mod base;
-use crate::base::{destroy, new_ad9833, new_ad9834, new_ad9837, new_ad9838, BitFlags as BF};
+use crate::base::{
+    destroy, destroy_any, new_ad5930, new_ad5932, new_ad9832, new_ad9833, new_ad9833_16,
+    new_ad9834, new_ad9835, new_ad9837, new_ad9838, new_any, new_flaky_ad9833, BitFlags as BF,
+    SpiTrans,
+};
 
 #[test]
 fn can_create_and_destroy_ad9833() {
@@ -58,8 +66,10 @@ fn can_reset() {
 #[test]
 fn cannot_set_too_fast_frequency() {
     let mut dev = new_ad9833(&[]);
-    dev.set_frequency(FreqReg::F0, 1 << 28)
-        .expect_err("Should return error");
+    match dev.set_frequency(FreqReg::F0, 1 << 28) {
+        Err(Error::ValueTooWide { bits: 28 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
     destroy(dev);
 }
 
@@ -106,8 +116,10 @@ fn can_select_freq1() {
 #[test]
 fn cannot_set_wrong_phase() {
     let mut dev = new_ad9833(&[]);
-    dev.set_phase(PhaseReg::P0, 1 << 12)
-        .expect_err("Should return error");
+    match dev.set_phase(PhaseReg::P0, 1 << 12) {
+        Err(Error::ValueTooWide { bits: 12 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
     destroy(dev);
 }
 
@@ -199,16 +211,20 @@ pd_test!(
 #[test]
 fn cannot_set_wrong_freq_msb() {
     let mut dev = new_ad9833(&[]);
-    dev.set_frequency_msb(FreqReg::F0, 1 << 14)
-        .expect_err("Should return error");
+    match dev.set_frequency_msb(FreqReg::F0, 1 << 14) {
+        Err(Error::ValueTooWide { bits: 14 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
     destroy(dev);
 }
 
 #[test]
 fn cannot_set_wrong_freq_lsb() {
     let mut dev = new_ad9833(&[]);
-    dev.set_frequency_lsb(FreqReg::F0, 1 << 14)
-        .expect_err("Should return error");
+    match dev.set_frequency_lsb(FreqReg::F0, 1 << 14) {
+        Err(Error::ValueTooWide { bits: 14 }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
     destroy(dev);
 }
 
@@ -234,8 +250,10 @@ fn can_set_freq_lsb() {
 #[test]
 fn cannot_set_ow_dac_ad9838() {
     let mut dev = new_ad9838(&[]);
-    dev.set_output_waveform(OW::SquareMsbOfDac)
-        .expect_err("Should return error");
+    match dev.set_output_waveform(OW::SquareMsbOfDac) {
+        Err(Error::UnsupportedWaveform(OW::SquareMsbOfDac)) => (),
+        _ => panic!("Should return Error::UnsupportedWaveform"),
+    }
     destroy(dev);
 }
 
@@ -291,3 +309,894 @@ fn can_set_control_source_hw_pins() {
     dev.set_control_source(ControlSource::HardwarePins).unwrap();
     destroy(dev);
 }
+
+fn play_a4<E>(dds: &mut dyn Dds<Error = Error<E>>) -> Result<(), Error<E>> {
+    dds.set_frequency(FreqReg::F0, 4724)?;
+    dds.enable()
+}
+
+#[test]
+fn can_use_ad9833_as_dds() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0 | 0x12, 0x74]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 0]),
+        SpiTrans::write_vec(vec![BF::B28, 0]),
+    ];
+    let mut dev = new_ad9833(&transitions);
+    assert_eq!(Some(SquareWavePin::Vout), dev.square_wave_pin());
+    play_a4(&mut dev).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_use_ad9838_as_dds() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0 | 0x12, 0x74]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 0]),
+        SpiTrans::write_vec(vec![BF::B28, 0]),
+    ];
+    let mut dev = new_ad9838(&transitions);
+    assert_eq!(Some(SquareWavePin::SignBitOut), dev.square_wave_pin());
+    play_a4(&mut dev).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn square_wave_output_replaces_triangle_by_sine_ad9834() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::RESET, BF::MODE]),
+        SpiTrans::write_vec(vec![BF::RESET, BF::OPBITEN | BF::DIV2]),
+    ];
+    let mut dev = new_ad9834(&transitions);
+    dev.set_output_waveform(OW::Triangle).unwrap();
+    dev.set_square_wave_output(SquareWave::MsbOfDac).unwrap();
+    destroy(dev);
+}
+
+macro_rules! square_wave_test {
+    ($name:ident, $create:ident, $sq:ident, $control:expr, $pin:ident) => {
+        #[test]
+        fn $name() {
+            let transitions = [SpiTrans::write_vec(vec![BF::RESET, $control])];
+            let mut dev = $create(&transitions);
+            let pin = dev.set_square_wave_output(SquareWave::$sq).unwrap();
+            assert_eq!(SquareWavePin::$pin, pin);
+            destroy(dev);
+        }
+    };
+}
+
+square_wave_test!(
+    can_set_sq_msb_ad9833,
+    new_ad9833,
+    MsbOfDac,
+    BF::OPBITEN | BF::DIV2,
+    Vout
+);
+square_wave_test!(
+    can_set_sq_msb_div2_ad9833,
+    new_ad9833,
+    MsbOfDacDiv2,
+    BF::OPBITEN,
+    Vout
+);
+square_wave_test!(
+    can_set_sq_msb_ad9838,
+    new_ad9838,
+    MsbOfDac,
+    BF::OPBITEN | BF::DIV2,
+    SignBitOut
+);
+square_wave_test!(
+    can_set_sq_msb_div2_ad9838,
+    new_ad9838,
+    MsbOfDacDiv2,
+    BF::OPBITEN,
+    SignBitOut
+);
+
+macro_rules! any_create_test {
+    ($name:ident, $variant:ident) => {
+        #[test]
+        fn $name() {
+            let dev = new_any(Variant::$variant, &[]);
+            assert_eq!(Variant::$variant, dev.variant());
+            destroy_any(dev);
+        }
+    };
+}
+
+any_create_test!(can_create_and_destroy_any_ad9833, Ad9833);
+any_create_test!(can_create_and_destroy_any_ad9834, Ad9834);
+any_create_test!(can_create_and_destroy_any_ad9837, Ad9837);
+any_create_test!(can_create_and_destroy_any_ad9838, Ad9838);
+any_create_test!(can_create_and_destroy_any_ad9832, Ad9832);
+any_create_test!(can_create_and_destroy_any_ad9835, Ad9835);
+any_create_test!(can_create_and_destroy_any_ad5930, Ad5930);
+any_create_test!(can_create_and_destroy_any_ad5932, Ad5932);
+
+#[test]
+fn can_set_freq0_any() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0 | 0xD, 0xEF]),
+        SpiTrans::write_vec(vec![BF::FREQ0 | 0x26, 0xAF]),
+    ];
+    let mut dev = new_any(Variant::Ad9837, &transitions);
+    dev.set_frequency(FreqReg::F0, 0x9AB_CDEF).unwrap();
+    destroy_any(dev);
+}
+
+#[test]
+fn can_use_any_as_dds() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0 | 0x12, 0x74]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 0]),
+        SpiTrans::write_vec(vec![BF::B28, 0]),
+    ];
+    let mut dev = new_any(Variant::Ad9834, &transitions);
+    assert_eq!(Some(SquareWavePin::SignBitOut), dev.square_wave_pin());
+    play_a4(&mut dev).unwrap();
+    destroy_any(dev);
+}
+
+#[test]
+fn can_set_sign_bit_output_any_ad9834() {
+    let transitions = [SpiTrans::write_vec(vec![BF::RESET, BF::OPBITEN])];
+    let mut dev = new_any(Variant::Ad9834, &transitions);
+    dev.set_sign_bit_output(SBO::SquareMsbOfDacDiv2).unwrap();
+    destroy_any(dev);
+}
+
+#[test]
+fn cannot_set_sign_bit_output_any_ad9833() {
+    let mut dev = new_any(Variant::Ad9833, &[]);
+    match dev.set_sign_bit_output(SBO::Comparator) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy_any(dev);
+}
+
+#[test]
+fn can_set_control_source_any_ad9838() {
+    let transitions = [SpiTrans::write_vec(vec![BF::RESET | BF::PIN_SW, 0])];
+    let mut dev = new_any(Variant::Ad9838, &transitions);
+    dev.set_control_source(ControlSource::HardwarePins).unwrap();
+    destroy_any(dev);
+}
+
+#[test]
+fn cannot_set_control_source_any_ad9837() {
+    let mut dev = new_any(Variant::Ad9837, &[]);
+    match dev.set_control_source(ControlSource::HardwarePins) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy_any(dev);
+}
+
+#[test]
+fn can_get_capabilities() {
+    let dev = new_ad9837(&[]);
+    assert_eq!(Variant::Ad9837, dev.variant());
+    let caps = dev.capabilities();
+    assert_eq!(16_000_000, caps.max_mclk_hz);
+    assert!(!caps.pin_control);
+    assert!(!caps.sign_bit_output);
+    assert_eq!(Some(SquareWavePin::Vout), caps.square_wave_pin);
+    assert_eq!(10, caps.dac_bits);
+    destroy(dev);
+}
+
+#[test]
+fn can_get_capabilities_ad9834() {
+    let dev = new_ad9834(&[]);
+    let caps = dev.capabilities();
+    // Datasheet value, higher than the 25 MHz of the AD9833
+    assert_eq!(75_000_000, caps.max_mclk_hz);
+    assert!(caps.pin_control);
+    assert!(caps.sign_bit_output);
+    assert_eq!(Some(SquareWavePin::SignBitOut), caps.square_wave_pin);
+    destroy(dev);
+}
+
+#[test]
+fn can_query_supported_waveforms() {
+    let supported = |variant: Variant| {
+        let caps = variant.capabilities();
+        [OW::Sinusoidal, OW::Triangle, OW::SquareMsbOfDac]
+            .map(|waveform| caps.supports_waveform(waveform))
+    };
+    assert_eq!([true, true, true], supported(Variant::Ad9833));
+    assert_eq!([true, true, false], supported(Variant::Ad9838));
+    assert_eq!([true, false, false], supported(Variant::Ad9835));
+    assert_eq!([true, true, false], supported(Variant::Ad5932));
+    assert!(Variant::Ad9833
+        .capabilities()
+        .supports_waveform(OW::SquareMsbOfDacDiv2));
+}
+
+#[test]
+fn can_set_mclk_frequency() {
+    let mut dev = new_ad9833(&[]);
+    dev.set_mclk_frequency(25_000_000).unwrap();
+    assert_eq!(Some(25_000_000), dev.mclk_frequency());
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_too_fast_mclk_frequency() {
+    let mut dev = new_ad9837(&[]);
+    match dev.set_mclk_frequency(25_000_000) {
+        Err(Error::InvalidMclkFrequency { max_hz: 16_000_000 }) => (),
+        _ => panic!("Should return Error::InvalidMclkFrequency"),
+    }
+    assert_eq!(None, dev.mclk_frequency());
+    destroy(dev);
+}
+
+#[test]
+fn can_set_frequency_hz() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ1 | 0x12, 0x74]),
+        SpiTrans::write_vec(vec![BF::FREQ1, 0]),
+    ];
+    let mut dev = new_ad9833(&transitions);
+    dev.set_mclk_frequency(25_000_000).unwrap();
+    dev.set_frequency_hz(FreqReg::F1, 440.0).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_frequency_hz_without_mclk() {
+    let mut dev = new_ad9833(&[]);
+    match dev.set_frequency_hz(FreqReg::F0, 440.0) {
+        Err(Error::MclkFrequencyNotSet) => (),
+        _ => panic!("Should return Error::MclkFrequencyNotSet"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_frequency_hz_above_half_mclk() {
+    let mut dev = new_ad9838(&[]);
+    dev.set_mclk_frequency(16_000_000).unwrap();
+    match dev.set_frequency_hz(FreqReg::F0, 8_000_001.0) {
+        Err(Error::FrequencyOutOfRange { max_hz }) => assert_eq!(8_000_000.0, max_hz),
+        _ => panic!("Should return Error::FrequencyOutOfRange"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn can_create_and_destroy_ad9832() {
+    let dev = new_ad9832(&[]);
+    destroy(dev);
+}
+
+#[test]
+fn can_create_and_destroy_ad9835() {
+    let dev = new_ad9835(&[]);
+    assert_eq!(Variant::Ad9835, dev.variant());
+    destroy(dev);
+}
+
+#[test]
+fn can_reset_ad9832() {
+    let transitions = [SpiTrans::write_vec(vec![0xD0, 0])];
+    let mut dev = new_ad9832(&transitions);
+    dev.reset().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_enable_ad9832() {
+    let transitions = [SpiTrans::write_vec(vec![0xC0, 0])];
+    let mut dev = new_ad9832(&transitions);
+    dev.enable().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_freq0_ad9832() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x30, 0x78]),
+        SpiTrans::write_vec(vec![0x21, 0x56]),
+        SpiTrans::write_vec(vec![0x32, 0x34]),
+        SpiTrans::write_vec(vec![0x23, 0x12]),
+    ];
+    let mut dev = new_ad9832(&transitions);
+    dev.set_frequency(FreqReg::F0, 0x1234_5678).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_freq1_ad9835() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x34, 0x78]),
+        SpiTrans::write_vec(vec![0x25, 0x56]),
+        SpiTrans::write_vec(vec![0x36, 0x34]),
+        SpiTrans::write_vec(vec![0x27, 0x12]),
+    ];
+    let mut dev = new_ad9835(&transitions);
+    dev.set_frequency(FreqReg::F1, 0x1234_5678).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_frequency_hz_ad9835() {
+    // 1 MHz with a 50 MHz MCLK: 0x051E_B852
+    let transitions = [
+        SpiTrans::write_vec(vec![0x30, 0x52]),
+        SpiTrans::write_vec(vec![0x21, 0xB8]),
+        SpiTrans::write_vec(vec![0x32, 0x1E]),
+        SpiTrans::write_vec(vec![0x23, 0x05]),
+    ];
+    let mut dev = new_ad9835(&transitions);
+    dev.set_mclk_frequency(50_000_000).unwrap();
+    dev.set_frequency_hz(FreqReg::F0, 1_000_000.0).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_too_fast_mclk_frequency_ad9832() {
+    let mut dev = new_ad9832(&[]);
+    dev.set_mclk_frequency(50_000_000)
+        .expect_err("Should return error");
+    destroy(dev);
+}
+
+#[test]
+fn can_set_phase0_ad9832() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x18, 0xBC]),
+        SpiTrans::write_vec(vec![0x09, 0x0A]),
+    ];
+    let mut dev = new_ad9832(&transitions);
+    dev.set_phase(PhaseReg::P0, 0xABC).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_phase3_ad9832() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x1E, 0xBC]),
+        SpiTrans::write_vec(vec![0x0F, 0x0A]),
+    ];
+    let mut dev = new_ad9832(&transitions);
+    dev.set_phase(ExtPhaseReg::P3, 0xABC).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_wrong_phase_ad9832() {
+    let mut dev = new_ad9832(&[]);
+    dev.set_phase(ExtPhaseReg::P2, 1 << 12)
+        .expect_err("Should return error");
+    destroy(dev);
+}
+
+#[test]
+fn can_select_freq1_ad9832() {
+    let transitions = [SpiTrans::write_vec(vec![0x68, 0])];
+    let mut dev = new_ad9832(&transitions);
+    dev.select_frequency(FreqReg::F1).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_select_phase2_and_freq1_ad9832() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x64, 0]),
+        SpiTrans::write_vec(vec![0x6C, 0]),
+    ];
+    let mut dev = new_ad9832(&transitions);
+    dev.select_phase(ExtPhaseReg::P2).unwrap();
+    dev.select_frequency(FreqReg::F1).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_control_source_and_sync_ad9832() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x90, 0]),
+        SpiTrans::write_vec(vec![0xB0, 0]),
+        SpiTrans::write_vec(vec![0xA0, 0]),
+        SpiTrans::write_vec(vec![0x80, 0]),
+    ];
+    let mut dev = new_ad9832(&transitions);
+    dev.set_control_source(ControlSource::Software).unwrap();
+    dev.enable_selection_sync().unwrap();
+    dev.set_control_source(ControlSource::HardwarePins).unwrap();
+    dev.disable_selection_sync().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_power_down_ad9832() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0xF0, 0]),
+        SpiTrans::write_vec(vec![0xD0, 0]),
+    ];
+    let mut dev = new_ad9832(&transitions);
+    dev.set_powered_down(PD::DacAndInternalClock).unwrap();
+    dev.set_powered_down(PD::Nothing).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_power_down_only_dac_ad9832() {
+    let mut dev = new_ad9832(&[]);
+    match dev.set_powered_down(PD::Dac) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn can_use_ad9835_as_dds() {
+    let mut dev = new_ad9835(&[]);
+    {
+        let dds: &mut dyn Dds<Error = _> = &mut dev;
+        assert_eq!(None, dds.square_wave_pin());
+        dds.set_output_waveform(OW::Sinusoidal).unwrap();
+        match dds.set_output_waveform(OW::Triangle) {
+            Err(Error::UnsupportedWaveform(OW::Triangle)) => (),
+            _ => panic!("Should return Error::UnsupportedWaveform"),
+        }
+        match dds.set_square_wave_output(SquareWave::MsbOfDac) {
+            Err(Error::Unsupported) => (),
+            _ => panic!("Should return Error::Unsupported"),
+        }
+    }
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_square_waveform_any_ad9832() {
+    let mut dev = new_any(Variant::Ad9832, &[]);
+    dev.set_output_waveform(OW::Sinusoidal).unwrap();
+    match dev.set_output_waveform(OW::SquareMsbOfDac) {
+        Err(Error::UnsupportedWaveform(OW::SquareMsbOfDac)) => (),
+        _ => panic!("Should return Error::UnsupportedWaveform"),
+    }
+    destroy_any(dev);
+}
+
+#[test]
+fn cannot_set_freq_msb_any_ad9832() {
+    let mut dev = new_any(Variant::Ad9832, &[]);
+    match dev.set_frequency_msb(FreqReg::F0, 0) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy_any(dev);
+}
+
+#[test]
+fn can_set_control_source_any_ad9835() {
+    let transitions = [SpiTrans::write_vec(vec![0x90, 0])];
+    let mut dev = new_any(Variant::Ad9835, &transitions);
+    dev.set_control_source(ControlSource::Software).unwrap();
+    destroy_any(dev);
+}
+
+fn sweep_config() -> SweepConfig {
+    SweepConfig {
+        start_frequency: 0x12_3456,
+        frequency_increment: -0x1234,
+        increments: 100,
+        increment_interval: IncrementInterval {
+            base: IncrementIntervalBase::MclkPeriods,
+            multiplier: IncrementIntervalMultiplier::X100,
+            count: 50,
+        },
+    }
+}
+
+#[test]
+fn can_create_and_destroy_ad5930() {
+    let dev = new_ad5930(&[]);
+    assert_eq!(Variant::Ad5930, dev.variant());
+    assert_eq!(24, dev.capabilities().frequency_bits);
+    destroy(dev);
+}
+
+#[test]
+fn can_create_and_destroy_ad5932() {
+    let dev = new_ad5932(&[]);
+    assert_eq!(Variant::Ad5932, dev.variant());
+    destroy(dev);
+}
+
+#[test]
+fn can_set_sweep_ad5932() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x0E, 0xD3]),
+        SpiTrans::write_vec(vec![0xC4, 0x56]),
+        SpiTrans::write_vec(vec![0xD1, 0x23]),
+        SpiTrans::write_vec(vec![0x22, 0x34]),
+        SpiTrans::write_vec(vec![0x38, 0x01]),
+        SpiTrans::write_vec(vec![0x10, 0x64]),
+        SpiTrans::write_vec(vec![0x70, 0x32]),
+    ];
+    let mut dev = new_ad5932(&transitions);
+    dev.set_sweep(&sweep_config()).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_sweep_ad5930() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0x0E, 0x83]),
+        SpiTrans::write_vec(vec![0xC4, 0x56]),
+        SpiTrans::write_vec(vec![0xD1, 0x23]),
+        SpiTrans::write_vec(vec![0x22, 0x34]),
+        SpiTrans::write_vec(vec![0x38, 0x01]),
+        SpiTrans::write_vec(vec![0x10, 0x64]),
+        SpiTrans::write_vec(vec![0x70, 0x32]),
+    ];
+    let mut dev = new_ad5930(&transitions);
+    dev.set_sweep(&sweep_config()).unwrap();
+    destroy(dev);
+}
+
+macro_rules! invalid_sweep_test {
+    ($name:ident, $field:ident, $value:expr) => {
+        #[test]
+        fn $name() {
+            let mut dev = new_ad5932(&[]);
+            let mut config = sweep_config();
+            config.$field = $value;
+            dev.set_sweep(&config).expect_err("Should return error");
+            destroy(dev);
+        }
+    };
+}
+
+invalid_sweep_test!(cannot_sweep_too_high_start, start_frequency, 1 << 24);
+invalid_sweep_test!(
+    cannot_sweep_too_high_increment,
+    frequency_increment,
+    1 << 23
+);
+invalid_sweep_test!(
+    cannot_sweep_too_low_increment,
+    frequency_increment,
+    -(1 << 23)
+);
+invalid_sweep_test!(cannot_sweep_too_few_increments, increments, 1);
+invalid_sweep_test!(cannot_sweep_too_many_increments, increments, 4096);
+invalid_sweep_test!(
+    cannot_sweep_too_long_interval,
+    increment_interval,
+    IncrementInterval {
+        base: IncrementIntervalBase::OutputCycles,
+        multiplier: IncrementIntervalMultiplier::X1,
+        count: 2048,
+    }
+);
+
+#[test]
+fn can_set_increment_interval_output_cycles_ad5932() {
+    let transitions = [SpiTrans::write_vec(vec![0x4F, 0xFF])];
+    let mut dev = new_ad5932(&transitions);
+    dev.set_increment_interval(IncrementInterval {
+        base: IncrementIntervalBase::OutputCycles,
+        multiplier: IncrementIntervalMultiplier::X5,
+        count: 2047,
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_set_start_frequency_hz_ad5932() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0xC0, 0x00]),
+        SpiTrans::write_vec(vec![0xD1, 0x00]),
+    ];
+    let mut dev = new_ad5932(&transitions);
+    dev.set_mclk_frequency(16_777_216).unwrap();
+    dev.set_start_frequency_hz(256.0 * 4096.0).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_configure_control_ad5932() {
+    let trigger = 0x0ED3 | 1 << 5;
+    let sync = trigger | 1 << 2 | 1 << 3;
+    let triangle = sync & !(1 << 9);
+    let msbout = triangle | 1 << 8;
+    let no_dac = msbout & !(1 << 10);
+    let transitions: Vec<SpiTrans> = [trigger, sync, triangle, msbout, no_dac]
+        .iter()
+        .map(|w| SpiTrans::write_vec(vec![(w >> 8) as u8, *w as u8]))
+        .collect();
+    let mut dev = new_ad5932(&transitions);
+    dev.set_increment_trigger(IncrementTrigger::External)
+        .unwrap();
+    dev.set_sync_output(SyncOutput::EndOfSweep).unwrap();
+    dev.set_output_waveform(OW::Triangle).unwrap();
+    assert_eq!(
+        SquareWavePin::MsbOut,
+        dev.set_square_wave_output(SquareWave::MsbOfDac).unwrap()
+    );
+    dev.set_powered_down(PD::Dac).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_unsupported_outputs_ad5932() {
+    let mut dev = new_ad5932(&[]);
+    dev.set_output_waveform(OW::SquareMsbOfDac)
+        .expect_err("Should return error");
+    match dev.set_square_wave_output(SquareWave::MsbOfDacDiv2) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    match dev.set_powered_down(PD::InternalClock) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn cannot_set_frequency_any_ad5930() {
+    let mut dev = new_any(Variant::Ad5930, &[]);
+    assert_eq!(Some(SquareWavePin::MsbOut), dev.square_wave_pin());
+    match dev.set_frequency(FreqReg::F0, 0) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    match dev.set_phase(PhaseReg::P0, 0) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy_any(dev);
+}
+
+#[test]
+fn error_reports_spi_error_kind() {
+    let error: Error<SpiErrorKind> = Error::Spi(SpiErrorKind::Other);
+    assert_eq!(Some(SpiErrorKind::Other), error.spi_error_kind());
+    let error: Error<SpiErrorKind> = Error::Unsupported;
+    assert_eq!(None, error.spi_error_kind());
+}
+
+#[test]
+fn error_displays_limit() {
+    let error: Error<SpiErrorKind> = Error::ValueTooWide { bits: 28 };
+    assert_eq!("value does not fit in 28 bits", error.to_string());
+    let error: Error<SpiErrorKind> = Error::OutOfRange { min: 2, max: 4095 };
+    assert_eq!("value is outside of the range 2 to 4095", error.to_string());
+}
+
+#[test]
+fn is_desynchronized_after_spi_error() {
+    let mut dev = new_flaky_ad9833(&[], &[0]);
+    assert!(!dev.is_desynchronized());
+    match dev.set_phase(PhaseReg::P0, 1) {
+        Err(Error::Spi(_)) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert!(dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn rewrites_control_after_spi_error() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 1]),
+        // The MSB write fails. The control register is written again
+        // although B28 is already set.
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 1]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 0]),
+    ];
+    let mut dev = new_flaky_ad9833(&transitions, &[2]);
+    dev.set_frequency(FreqReg::F0, 1)
+        .expect_err("Should return error");
+    dev.set_frequency(FreqReg::F0, 1).unwrap();
+    assert!(!dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn can_resync() {
+    let transitions = [SpiTrans::write_vec(vec![BF::RESET, 0])];
+    let mut dev = new_flaky_ad9833(&transitions, &[0]);
+    dev.enable().expect_err("Should return error");
+    dev.resync().unwrap();
+    assert!(!dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+static DELAYS: AtomicUsize = AtomicUsize::new(0);
+
+fn count_delay() {
+    DELAYS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn retries_whole_frequency_write() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 1]),
+        // The MSB write fails. The control register is written again
+        // before the LSB and the MSB.
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 1]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 0]),
+    ];
+    let mut dev = new_flaky_ad9833(&transitions, &[2]);
+    dev.set_retry_policy(RetryPolicy {
+        max_attempts: 3,
+        delay: Some(count_delay),
+    });
+    dev.set_frequency(FreqReg::F0, 1).unwrap();
+    assert_eq!(1, dev.retry_count());
+    assert_eq!(1, DELAYS.load(Ordering::SeqCst));
+    assert!(!dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn resyncs_before_replaying_frequency_write() {
+    let transitions = [
+        SpiTrans::write_vec(vec![0, 0]),
+        SpiTrans::write_vec(vec![BF::B28, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ1 | 0x0D, 0xEF]),
+        // The MSB write fails
+        SpiTrans::write_vec(vec![BF::B28, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ1 | 0x0D, 0xEF]),
+        SpiTrans::write_vec(vec![BF::FREQ1 | 0x26, 0xAF]),
+    ];
+    let mut dev = new_flaky_ad9833(&transitions, &[3]);
+    dev.set_retry_policy(RetryPolicy {
+        max_attempts: 2,
+        delay: None,
+    });
+    dev.enable().unwrap();
+    dev.set_frequency(FreqReg::F1, 0x9AB_CDEF).unwrap();
+    assert_eq!(1, dev.retry_count());
+    assert!(!dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn does_not_retry_resync_within_replay() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::RESET, 0]),
+        SpiTrans::write_vec(vec![BF::FREQ0, 1]),
+        // The MSB write and the control write of the second attempt fail.
+        // No third attempt is made.
+    ];
+    let mut dev = new_flaky_ad9833(&transitions, &[2, 3]);
+    dev.set_retry_policy(RetryPolicy {
+        max_attempts: 2,
+        delay: None,
+    });
+    match dev.set_frequency(FreqReg::F0, 1) {
+        Err(Error::Spi(_)) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert_eq!(1, dev.retry_count());
+    assert!(dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let mut dev = new_flaky_ad9833(&[], &[0, 1]);
+    dev.set_retry_policy(RetryPolicy {
+        max_attempts: 2,
+        delay: None,
+    });
+    match dev.enable() {
+        Err(Error::Spi(_)) => (),
+        _ => panic!("Should return Error::Spi"),
+    }
+    assert_eq!(1, dev.retry_count());
+    assert!(dev.is_desynchronized());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn does_not_retry_by_default() {
+    let mut dev = new_flaky_ad9833(&[], &[0]);
+    dev.enable().expect_err("Should return error");
+    assert_eq!(0, dev.retry_count());
+    dev.destroy().mock.done();
+}
+
+#[test]
+fn can_write_control_word() {
+    let transitions = [
+        SpiTrans::write_vec(vec![BF::B28 | BF::FSELECT, BF::OPBITEN | BF::DIV2]),
+        // The driver state is updated
+        SpiTrans::write_vec(vec![
+            BF::B28 | BF::FSELECT | BF::PSELECT,
+            BF::OPBITEN | BF::DIV2,
+        ]),
+    ];
+    let mut dev = new_ad9833(&transitions);
+    let word = ControlWord::new()
+        .b28(true)
+        .fselect(FreqReg::F1)
+        .opbiten(true)
+        .div2(true);
+    dev.write_control_word(word).unwrap();
+    assert_eq!(word, dev.control_word());
+    dev.select_phase(PhaseReg::P1).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_write_pin_control_word_ad9834() {
+    let transitions = [SpiTrans::write_vec(vec![BF::PIN_SW, BF::SIGN_PIB])];
+    let mut dev = new_ad9834(&transitions);
+    let word = ControlWord::new().pin_sw(true).sign_pib(true);
+    dev.write_control_word(word).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_write_pin_control_word_ad9833() {
+    let mut dev = new_ad9833(&[]);
+    match dev.write_control_word(ControlWord::new().pin_sw(true)) {
+        Err(Error::Unsupported) => (),
+        _ => panic!("Should return Error::Unsupported"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn cannot_write_triangle_with_digital_output_control_word() {
+    let mut dev = new_ad9834(&[]);
+    match dev.write_control_word(ControlWord::new().opbiten(true).mode(true)) {
+        Err(Error::UnsupportedWaveform(OW::Triangle)) => (),
+        _ => panic!("Should return Error::UnsupportedWaveform"),
+    }
+    destroy(dev);
+}
+
+#[test]
+fn control_word_builder_clears_bits() {
+    let word = ControlWord::new()
+        .reset(true)
+        .sleep1(true)
+        .sleep12(true)
+        .hlb(true)
+        .div2(true);
+    assert_eq!(0x11C8, word.bits());
+    let word = word.reset(false).sleep1(false).sleep12(false);
+    assert_eq!(0x1008, word.bits());
+}
+
+#[test]
+fn can_write_16_bit_frames() {
+    let mut dev = new_ad9833_16();
+    dev.reset().unwrap();
+    dev.set_frequency(FreqReg::F1, 0x9AB_CDEF).unwrap();
+    dev.enable().unwrap();
+    let spi = dev.destroy().destroy();
+    assert_eq!(
+        vec![
+            vec![0x0100],
+            vec![0x2100],
+            vec![0x8DEF],
+            vec![0xA6AF],
+            vec![0x2000]
+        ],
+        spi.transactions
+    );
+}
@@ -0,0 +1,121 @@
+use ad983x::{Ad983x, FrequencyRegister as FreqReg, PhaseRegister as PhaseReg};
+use embedded_hal_mock::eh1::spi::{Mock, Transaction};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+use std::sync::Once;
+
+struct Logger;
+
+thread_local! {
+    static RECORDS: RefCell<Vec<(Level, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+impl Log for Logger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        RECORDS.with(|records| {
+            records
+                .borrow_mut()
+                .push((record.level(), record.args().to_string()))
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+static INIT: Once = Once::new();
+
+/// Collect the records emitted while running the given closure.
+fn records<F: FnOnce()>(f: F) -> Vec<(Level, String)> {
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+    RECORDS.with(|records| records.borrow_mut().clear());
+    f();
+    RECORDS.with(|records| records.borrow_mut().drain(..).collect())
+}
+
+fn writes(words: &[[u8; 2]]) -> Mock<u8> {
+    let transactions: Vec<Transaction<u8>> = words
+        .iter()
+        .flat_map(|word| {
+            [
+                Transaction::transaction_start(),
+                Transaction::write_vec(word.to_vec()),
+                Transaction::transaction_end(),
+            ]
+        })
+        .collect();
+    Mock::new(transactions.iter())
+}
+
+#[test]
+fn traces_set_frequency() {
+    let mut dds = Ad983x::new_ad9833(writes(&[[0x21, 0x00], [0x52, 0x74], [0x40, 0x01]]));
+    let records = records(|| dds.set_frequency(FreqReg::F0, 0x1274 | (1 << 14)).unwrap());
+    assert_eq!(
+        vec![
+            (Level::Debug, "set_frequency F0 = 0x5274".to_string()),
+            (Level::Debug, "control 0x0100 -> 0x2100".to_string()),
+            (Level::Trace, "word 0x2100: CONTROL = 0x2100".to_string()),
+            (Level::Trace, "word 0x5274: FREQ0 = 0x1274".to_string()),
+            (Level::Trace, "word 0x4001: FREQ0 = 0x1".to_string()),
+        ],
+        records
+    );
+    dds.destroy().done();
+}
+
+#[test]
+fn traces_set_frequency_hz_once() {
+    let mut dds = Ad983x::new_ad9833(writes(&[[0x21, 0x00], [0x69, 0xF1], [0x40, 0x00]]));
+    dds.set_mclk_frequency(25_000_000).unwrap();
+    let records = records(|| dds.set_frequency_hz(FreqReg::F0, 1000.0).unwrap());
+    assert_eq!(
+        vec![
+            (Level::Debug, "set_frequency F0 = 0x29f1".to_string()),
+            (Level::Debug, "control 0x0100 -> 0x2100".to_string()),
+            (Level::Trace, "word 0x2100: CONTROL = 0x2100".to_string()),
+            (Level::Trace, "word 0x69f1: FREQ0 = 0x29f1".to_string()),
+            (Level::Trace, "word 0x4000: FREQ0 = 0x0".to_string()),
+        ],
+        records
+    );
+    dds.destroy().done();
+}
+
+#[test]
+fn traces_select_phase() {
+    let mut dds = Ad983x::new_ad9834(writes(&[[0x05, 0x00], [0xE4, 0x00]]));
+    let records = records(|| {
+        dds.select_phase(PhaseReg::P1).unwrap();
+        dds.set_phase(PhaseReg::P1, 1024).unwrap();
+    });
+    assert_eq!(
+        vec![
+            (Level::Debug, "select_phase P1".to_string()),
+            (Level::Debug, "control 0x0100 -> 0x0500".to_string()),
+            (Level::Trace, "word 0x0500: CONTROL = 0x500".to_string()),
+            (Level::Debug, "set_phase P1 = 1024".to_string()),
+            (Level::Trace, "word 0xe400: PHASE1 = 0x400".to_string()),
+        ],
+        records
+    );
+    dds.destroy().done();
+}
+
+#[test]
+fn does_not_trace_invalid_operations() {
+    let mut dds = Ad983x::new_ad9833(writes(&[]));
+    let records = records(|| {
+        dds.set_phase(PhaseReg::P0, 1 << 12)
+            .expect_err("Should return error");
+    });
+    assert!(records.is_empty());
+    dds.destroy().done();
+}
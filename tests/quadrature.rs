@@ -0,0 +1,166 @@
+use ad983x::{
+    group::GroupError, marker, quadrature::QuadraturePair, Ad983x, Error,
+    FrequencyRegister as FreqReg,
+};
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+/// Destination of a word
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    I,
+    Q,
+    Both,
+}
+
+type Words = Rc<RefCell<Vec<(Target, u16)>>>;
+
+/// SPI device recording the words written
+struct Recorder {
+    target: Target,
+    words: Words,
+}
+
+impl ErrorType for Recorder {
+    type Error = Infallible;
+}
+
+impl SpiDevice for Recorder {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+        for operation in operations {
+            if let Operation::Write(bytes) = operation {
+                let word = u16::from_be_bytes([bytes[0], bytes[1]]);
+                self.words.borrow_mut().push((self.target, word));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn new_pair(words: &Words) -> QuadraturePair<Recorder, marker::Ad9833Ad9837, Recorder> {
+    let recorder = |target| Recorder {
+        target,
+        words: words.clone(),
+    };
+    QuadraturePair::new(
+        Ad983x::new_ad9833(recorder(Target::I)),
+        Ad983x::new_ad9833(recorder(Target::Q)),
+        recorder(Target::Both),
+    )
+}
+
+#[test]
+fn starts_in_quadrature() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    pair.start(0x1274).unwrap();
+    let expected = |target| {
+        let phase = match target {
+            Target::I => 0xC000,
+            _ => 0xC400,
+        };
+        [0x0100, 0x2100, 0x5274, 0x4000, phase].map(|word| (target, word))
+    };
+    let mut all = expected(Target::I).to_vec();
+    all.extend(expected(Target::Q));
+    all.push((Target::Both, 0x2000));
+    assert_eq!(all, *words.borrow());
+}
+
+#[test]
+fn applies_phase_trim() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    pair.set_phase_trim(-1025).unwrap();
+    assert_eq!(-1025, pair.phase_trim());
+    pair.start(1).unwrap();
+    pair.set_phase_trim(5).unwrap();
+    let words = words.borrow();
+    assert_eq!((Target::Q, 0xCFFF), words[0]);
+    assert_eq!((Target::Q, 0xCFFF), words[10]);
+    assert_eq!((Target::Q, 0xC405), *words.last().unwrap());
+}
+
+#[test]
+fn broadcasts_frequency() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    pair.start(1).unwrap();
+    words.borrow_mut().clear();
+    pair.set_frequency(0x9AB_CDEF).unwrap();
+    assert_eq!(
+        vec![(Target::Both, 0x4DEF), (Target::Both, 0x66AF)],
+        *words.borrow()
+    );
+}
+
+#[test]
+fn broadcasts_control_before_frequency_if_needed() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    pair.start(1).unwrap();
+    let control = pair.i().control_word().fselect(FreqReg::F1).b28(false);
+    pair.i().write_control_word(control).unwrap();
+    pair.q().write_control_word(control).unwrap();
+    words.borrow_mut().clear();
+    pair.set_frequency(2).unwrap();
+    assert_eq!(
+        vec![
+            (Target::Both, 0x2800),
+            (Target::Both, 0x8002),
+            (Target::Both, 0x8000),
+        ],
+        *words.borrow()
+    );
+}
+
+#[test]
+fn cannot_set_frequency_with_different_control() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    pair.start(1).unwrap();
+    pair.i().select_frequency(FreqReg::F1).unwrap();
+    words.borrow_mut().clear();
+    match pair.set_frequency(2) {
+        Err(GroupError::ControlMismatch { index: 1 }) => (),
+        _ => panic!("Should return GroupError::ControlMismatch"),
+    }
+    assert!(words.borrow().is_empty());
+}
+
+#[test]
+fn can_set_frequency_hz() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    match pair.set_frequency_hz(1000.0) {
+        Err(GroupError::Device {
+            index: 0,
+            error: Error::MclkFrequencyNotSet,
+        }) => (),
+        _ => panic!("Should return Error::MclkFrequencyNotSet"),
+    }
+    pair.i().set_mclk_frequency(25_000_000).unwrap();
+    pair.start(0).unwrap();
+    words.borrow_mut().clear();
+    pair.set_frequency_hz(1000.0).unwrap();
+    assert_eq!(
+        vec![(Target::Both, 0x69F1), (Target::Both, 0x4000)],
+        *words.borrow()
+    );
+}
+
+#[test]
+fn cannot_start_with_too_wide_frequency() {
+    let words = Words::default();
+    let mut pair = new_pair(&words);
+    match pair.start(1 << 28) {
+        Err(GroupError::Device {
+            index: 0,
+            error: Error::ValueTooWide { bits: 28 },
+        }) => (),
+        _ => panic!("Should return Error::ValueTooWide"),
+    }
+    assert!(words.borrow().is_empty());
+    let (i, q, _) = pair.destroy();
+    assert!(!i.is_desynchronized() && !q.is_desynchronized());
+}
@@ -0,0 +1,78 @@
+use ad983x::{emulator::Emulator, wav::WavRecorder, Ad983x, FrequencyRegister as FreqReg, Variant};
+use std::time::Duration;
+
+fn new_recorder() -> (WavRecorder, Ad983x<Emulator, ad983x::marker::Ad9833Ad9837>) {
+    let emulator = Emulator::new(Variant::Ad9833).unwrap();
+    let mut dds = Ad983x::new_ad9833(emulator.clone());
+    dds.reset().unwrap();
+    dds.set_frequency(FreqReg::F0, 1 << 26).unwrap();
+    dds.set_frequency(FreqReg::F1, 1 << 25).unwrap();
+    dds.enable().unwrap();
+    (WavRecorder::new(emulator, 8_000, 8_000).unwrap(), dds)
+}
+
+#[test]
+fn cannot_create_with_zero_sample_rate() {
+    let emulator = Emulator::new(Variant::Ad9833).unwrap();
+    assert!(WavRecorder::new(emulator, 8_000, 0).is_none());
+}
+
+#[test]
+fn renders_until_time() {
+    let (mut recorder, _dds) = new_recorder();
+    recorder.render_until(Duration::from_millis(1));
+    assert_eq!(8, recorder.samples().len());
+    assert_eq!(Duration::from_millis(1), recorder.time());
+    recorder.render_until(Duration::from_micros(500));
+    assert_eq!(8, recorder.samples().len());
+    assert_eq!(
+        &[32767, 32, -32767, 32, 32767, 32, -32767, 32],
+        recorder.samples()
+    );
+}
+
+#[test]
+fn runs_timed_commands() {
+    let (mut recorder, mut dds) = new_recorder();
+    recorder
+        .at(Duration::from_micros(500), || {
+            dds.select_frequency(FreqReg::F1)
+        })
+        .unwrap();
+    recorder.render_until(Duration::from_millis(1));
+    assert_eq!(
+        &[32767, 32, -32767, 32, 23158, 32767, 23158, 32],
+        recorder.samples()
+    );
+}
+
+#[test]
+fn writes_wav() {
+    let (mut recorder, _dds) = new_recorder();
+    recorder.render_until(Duration::from_micros(250));
+    let mut wav = Vec::new();
+    recorder.write_wav(&mut wav).unwrap();
+    let mut expected = Vec::new();
+    expected.extend_from_slice(b"RIFF");
+    expected.extend_from_slice(&40u32.to_le_bytes());
+    expected.extend_from_slice(b"WAVEfmt ");
+    expected.extend_from_slice(&[16, 0, 0, 0, 1, 0, 1, 0]);
+    expected.extend_from_slice(&8000u32.to_le_bytes());
+    expected.extend_from_slice(&16000u32.to_le_bytes());
+    expected.extend_from_slice(&[2, 0, 16, 0]);
+    expected.extend_from_slice(b"data");
+    expected.extend_from_slice(&4u32.to_le_bytes());
+    expected.extend_from_slice(&32767i16.to_le_bytes());
+    expected.extend_from_slice(&32i16.to_le_bytes());
+    assert_eq!(expected, wav);
+}
+
+#[test]
+fn cannot_write_wav_with_too_high_sample_rate() {
+    let emulator = Emulator::new(Variant::Ad9833).unwrap();
+    let recorder = WavRecorder::new(emulator, 25_000_000, u32::MAX).unwrap();
+    let mut wav = Vec::new();
+    let error = recorder.write_wav(&mut wav).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+    assert!(wav.is_empty());
+}